@@ -0,0 +1,85 @@
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+use crate::simulation::LanderState;
+use crate::ui::{EditorState, SimulationState};
+
+// Sound files, loaded through the asset server. If they're missing the handles never
+// load and the players stay silent.
+const ENGINE_SOUND: &str = "audio/engine.ogg";
+const EXPLOSION_SOUND: &str = "audio/explosion.ogg";
+
+// Engine loop volume at full throttle, and its playback speed from idle to full throttle
+const ENGINE_MAX_VOLUME: f32 = 0.6;
+const ENGINE_MIN_SPEED: f32 = 0.8;
+const ENGINE_MAX_SPEED: f32 = 1.2;
+const EXPLOSION_VOLUME: f32 = 0.8;
+
+#[derive(Resource, Default)]
+pub struct AudioSettings {
+    pub muted: bool, // Master mute for all game sounds
+}
+
+// Looping engine rumble, paused while the engine is off
+#[derive(Component)]
+pub struct EngineSound;
+
+pub struct GameAudioPlugin;
+
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AudioSettings>()
+            .add_systems(Startup, spawn_engine_sound)
+            .add_systems(Update, update_engine_sound);
+    }
+}
+
+fn spawn_engine_sound(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        AudioPlayer::new(asset_server.load(ENGINE_SOUND)),
+        PlaybackSettings::LOOP.with_volume(Volume::ZERO).paused(),
+        EngineSound,
+    ));
+}
+
+// Follow the throttle: louder and higher pitched as it opens, silent when it's closed
+fn update_engine_sound(
+    settings: Res<AudioSettings>,
+    editor_state: Res<EditorState>,
+    lander_state: Res<LanderState>,
+    sinks: Query<&AudioSink, With<EngineSound>>,
+) {
+    let Ok(sink) = sinks.get_single() else {
+        return;
+    };
+
+    let thrust = lander_state.thrust_level;
+    let firing = thrust > 0.0
+        && !settings.muted
+        && !lander_state.crashed
+        && editor_state.simulation_state == SimulationState::Running;
+    if firing {
+        sink.set_volume(thrust * ENGINE_MAX_VOLUME);
+        sink.set_speed(ENGINE_MIN_SPEED + (ENGINE_MAX_SPEED - ENGINE_MIN_SPEED) * thrust);
+        if sink.is_paused() {
+            sink.play();
+        }
+    } else if !sink.is_paused() {
+        sink.pause();
+    }
+}
+
+// One-shot crash sound, despawned once it has played
+pub fn play_explosion(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    settings: &AudioSettings,
+) {
+    if settings.muted {
+        return;
+    }
+    commands.spawn((
+        AudioPlayer::new(asset_server.load(EXPLOSION_SOUND)),
+        PlaybackSettings::DESPAWN.with_volume(Volume::new(EXPLOSION_VOLUME)),
+    ));
+}
@@ -0,0 +1,43 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use serde::{Deserialize, Serialize};
+
+// A randomized scenario everyone in a class can fly: the level, the seed its random
+// elements start from and whether the initial state is dispersed
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Challenge {
+    pub level: usize,
+    pub seed: u64,
+    #[serde(default)]
+    pub dispersion: bool, // Codes made before this was added fly without dispersion
+}
+
+// Short, copy-paste friendly code for a challenge
+pub fn encode(challenge: &Challenge) -> String {
+    let json = serde_json::to_string(challenge).expect("challenge serializes to JSON");
+    URL_SAFE_NO_PAD.encode(json)
+}
+
+pub fn decode(code: &str) -> Result<Challenge, String> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(code.trim())
+        .map_err(|_| "Not a valid challenge code".to_string())?;
+    serde_json::from_slice(&bytes).map_err(|_| "Not a valid challenge code".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_round_trips() {
+        for dispersion in [false, true] {
+            let challenge = Challenge {
+                level: 4,
+                seed: u64::MAX,
+                dispersion,
+            };
+            assert_eq!(decode(&encode(&challenge)), Ok(challenge));
+        }
+        assert!(decode("not a code").is_err());
+    }
+}
@@ -0,0 +1,127 @@
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::headless::{run_headless, RunOutcome, HEADLESS_MAX_TIME};
+use crate::levels::{LevelConfig, LevelList};
+use crate::rhai_api::ScriptEngine;
+use crate::simulation::{score_run, star_rating};
+
+#[derive(Serialize, Default)]
+struct GradeReport {
+    passed: bool,
+    fuel_remaining: f32,  // kg
+    touchdown_speed: f32, // m/s
+    accuracy: f32,        // distance from the target (m)
+    time: f32,            // simulated seconds
+    star_rating: u8,
+    failure_reason: Option<String>,
+}
+
+// `gnc-trainer --grade <level> <script.rhai>` runs the script headlessly on the level
+// (file name like "level3" or the level's display name) and prints the result as JSON.
+// Returns the process exit code when grading was requested: 0 passed, 1 failed, 2 usage error.
+pub fn grade_from_args() -> Option<i32> {
+    let args: Vec<String> = std::env::args().collect();
+    let index = args.iter().position(|arg| arg == "--grade")?;
+    let (Some(level), Some(script_path)) = (args.get(index + 1), args.get(index + 2)) else {
+        eprintln!("Usage: gnc-trainer --grade <level> <script.rhai>");
+        return Some(2);
+    };
+
+    match grade(level, script_path) {
+        Ok(report) => {
+            match serde_json::to_string_pretty(&report) {
+                Ok(json) => println!("{}", json),
+                Err(e) => {
+                    eprintln!("Failed to serialize results: {}", e);
+                    return Some(2);
+                }
+            }
+            Some(if report.passed { 0 } else { 1 })
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            Some(2)
+        }
+    }
+}
+
+fn grade(level: &str, script_path: &str) -> Result<GradeReport, String> {
+    let config = load_level(level)?;
+    let script = fs::read_to_string(script_path)
+        .map_err(|e| format!("Failed to read script '{}': {}", script_path, e))?;
+
+    let mut script_engine = ScriptEngine::default();
+    script_engine.set_level(&config);
+    if let Err(error) = script_engine.compile_script(&script) {
+        return Ok(GradeReport {
+            failure_reason: Some(error),
+            ..Default::default()
+        });
+    }
+    script_engine.activate_script();
+
+    let run = run_headless(&config, &mut script_engine, 0);
+    let score = score_run(&run.state, &config, &run.trajectory);
+    let passed = matches!(run.outcome, RunOutcome::Landed);
+    let failure_reason = match run.outcome {
+        RunOutcome::Landed => None,
+        RunOutcome::Crashed if script_engine.abort_message.is_some() => Some(format!(
+            "Aborted: {}",
+            script_engine.abort_message.clone().unwrap_or_default()
+        )),
+        RunOutcome::Crashed if run.state.engine_overheated => {
+            Some("Mission failed! The engine overheated.".to_string())
+        }
+        RunOutcome::Crashed => Some(config.failure_message.clone()),
+        RunOutcome::TimedOut => Some(format!(
+            "Did not finish within {} simulated seconds",
+            HEADLESS_MAX_TIME
+        )),
+        RunOutcome::ScriptError(error) => Some(error),
+    };
+
+    Ok(GradeReport {
+        passed,
+        fuel_remaining: score.fuel_remaining,
+        touchdown_speed: score.touchdown_speed,
+        accuracy: score.accuracy,
+        time: score.time,
+        star_rating: star_rating(passed, &score, &config),
+        failure_reason,
+    })
+}
+
+// Same lookup order as Bevy's asset server for the assets folder
+fn assets_dir() -> PathBuf {
+    let base = std::env::var_os("BEVY_ASSET_ROOT")
+        .or_else(|| std::env::var_os("CARGO_MANIFEST_DIR"))
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(PathBuf::from))
+        })
+        .unwrap_or_default();
+    base.join("assets")
+}
+
+fn load_level(name: &str) -> Result<LevelConfig, String> {
+    let levels_dir = assets_dir().join("levels");
+    let read = |file: &str| {
+        let path = levels_dir.join(file);
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+    };
+
+    let list: LevelList = ron::de::from_str(&read("level_list.ron")?)
+        .map_err(|e| format!("Failed to parse level list: {}", e))?;
+    for level_file in &list.levels {
+        let config: LevelConfig = ron::de::from_str(&read(&format!("{}.ron", level_file))?)
+            .map_err(|e| format!("Failed to parse level '{}': {}", level_file, e))?;
+        if level_file == name || config.name == name {
+            return Ok(config);
+        }
+    }
+    Err(format!("Unknown level '{}'", name))
+}
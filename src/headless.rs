@@ -0,0 +1,221 @@
+use crate::levels::LevelConfig;
+use crate::rhai_api::ScriptEngine;
+use crate::simulation::{reset_lander, step_simulation, LanderState, Trajectory, PHYSICS_DT};
+
+// Headless runs take the same fixed steps as the interactive simulation, so a graded
+// run matches what the student saw on screen
+pub const HEADLESS_DT: f32 = PHYSICS_DT;
+// Simulated time after which a run that neither landed nor crashed is stopped
+pub const HEADLESS_MAX_TIME: f32 = 600.0;
+// Shorter cutoff for batch trials, so a script that hovers can't stall the batch
+pub const TRIAL_MAX_TIME: f32 = 180.0;
+
+pub enum RunOutcome {
+    Landed,
+    Crashed,
+    TimedOut,
+    ScriptError(String),
+}
+
+pub struct HeadlessRun {
+    pub state: LanderState,
+    pub trajectory: Trajectory,
+    pub outcome: RunOutcome,
+}
+
+// Fly the level with the script engine's active script, without rendering, until
+// the run ends or HEADLESS_MAX_TIME runs out
+pub fn run_headless(
+    config: &LevelConfig,
+    script_engine: &mut ScriptEngine,
+    seed: u64,
+) -> HeadlessRun {
+    let state = LanderState {
+        seed,
+        ..Default::default()
+    };
+    fly(state, config, script_engine, HEADLESS_MAX_TIME)
+}
+
+// Summary of one batch trial
+pub struct TrialResult {
+    pub seed: u64,
+    pub outcome: RunOutcome,
+    pub touchdown_speed: Option<f32>, // m/s, for runs that reached the ground
+    pub fuel_remaining: f32,          // kg
+}
+
+// One trial of a batch, flown a few steps at a time so a long trial can be spread
+// over several frames. The level's dispersion is applied with the trial's seed and the
+// run is cut off after TRIAL_MAX_TIME.
+pub struct Trial {
+    seed: u64,
+    state: LanderState,
+    trajectory: Trajectory,
+    steps_left: usize,
+}
+
+impl Trial {
+    pub fn start(config: &LevelConfig, script_engine: &mut ScriptEngine, seed: u64) -> Trial {
+        let mut state = LanderState {
+            seed,
+            dispersion: true,
+            ..Default::default()
+        };
+        let mut trajectory = Trajectory::default();
+        prepare(&mut state, config, script_engine, &mut trajectory);
+        Trial {
+            seed,
+            state,
+            trajectory,
+            steps_left: max_steps(TRIAL_MAX_TIME),
+        }
+    }
+
+    // Take steps until the trial ends or `budget` runs out, spending the budget as it
+    // goes. Returns the result once the trial has ended.
+    pub fn advance(
+        &mut self,
+        config: &LevelConfig,
+        script_engine: &mut ScriptEngine,
+        budget: &mut usize,
+    ) -> Option<TrialResult> {
+        while *budget > 0 {
+            if self.steps_left == 0 {
+                return Some(self.result(RunOutcome::TimedOut));
+            }
+            *budget -= 1;
+            self.steps_left -= 1;
+            if let Some(outcome) =
+                step(&mut self.state, config, script_engine, &mut self.trajectory)
+            {
+                return Some(self.result(outcome));
+            }
+        }
+        (self.steps_left == 0).then(|| self.result(RunOutcome::TimedOut))
+    }
+
+    fn result(&self, outcome: RunOutcome) -> TrialResult {
+        TrialResult {
+            seed: self.seed,
+            touchdown_speed: self
+                .state
+                .touchdown_velocity
+                .map(|velocity| velocity.length()),
+            fuel_remaining: self.state.fuel,
+            outcome,
+        }
+    }
+}
+
+fn max_steps(max_time: f32) -> usize {
+    (max_time / HEADLESS_DT).ceil() as usize
+}
+
+// Reset the lander from `state`'s seed and settings, and the script's state with it
+fn prepare(
+    state: &mut LanderState,
+    config: &LevelConfig,
+    script_engine: &mut ScriptEngine,
+    trajectory: &mut Trajectory,
+) {
+    reset_lander(state, config, trajectory);
+    script_engine.clear_user_state();
+    script_engine.reset_runtime_state();
+    script_engine.error_message = None;
+}
+
+// Take one step, returning the outcome if it ended the run
+fn step(
+    state: &mut LanderState,
+    config: &LevelConfig,
+    script_engine: &mut ScriptEngine,
+    trajectory: &mut Trajectory,
+) -> Option<RunOutcome> {
+    let elapsed = state.elapsed;
+    step_simulation(state, config, script_engine, trajectory, HEADLESS_DT);
+
+    if let Some(error) = script_engine.error_message.take() {
+        return Some(RunOutcome::ScriptError(error.message));
+    }
+    if state.landed {
+        return Some(RunOutcome::Landed);
+    }
+    if state.crashed {
+        return Some(RunOutcome::Crashed);
+    }
+    if state.elapsed == elapsed {
+        // The script produced no control output, so time can't advance
+        return Some(RunOutcome::ScriptError(
+            "Script did not produce a control output".into(),
+        ));
+    }
+    None
+}
+
+// Reset the lander from `state`'s seed and settings and step it until the run ends
+// or `max_time` runs out
+fn fly(
+    mut state: LanderState,
+    config: &LevelConfig,
+    script_engine: &mut ScriptEngine,
+    max_time: f32,
+) -> HeadlessRun {
+    let mut trajectory = Trajectory::default();
+    prepare(&mut state, config, script_engine, &mut trajectory);
+
+    let outcome = (0..max_steps(max_time))
+        .find_map(|_| step(&mut state, config, script_engine, &mut trajectory))
+        .unwrap_or(RunOutcome::TimedOut);
+
+    HeadlessRun {
+        state,
+        trajectory,
+        outcome,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Holds the lander at its starting altitude, so the trial runs until it's cut off
+    const HOVER_SCRIPT: &str =
+        "fn control(state) { 0.43 + 0.05 * (50.0 - state[\"y\"]) - 0.3 * state[\"vy\"] }";
+
+    fn engine(config: &LevelConfig) -> ScriptEngine {
+        let mut engine = ScriptEngine::default();
+        engine.set_level(config);
+        engine.compile_script(HOVER_SCRIPT).unwrap();
+        assert!(engine.activate_script());
+        engine
+    }
+
+    fn fly_trial(config: &LevelConfig, budget_per_frame: usize) -> (TrialResult, usize) {
+        let mut script_engine = engine(config);
+        let mut trial = Trial::start(config, &mut script_engine, 7);
+        let mut frames = 0;
+        loop {
+            frames += 1;
+            let mut budget = budget_per_frame;
+            if let Some(result) = trial.advance(config, &mut script_engine, &mut budget) {
+                return (result, frames);
+            }
+            assert_eq!(budget, 0);
+        }
+    }
+
+    #[test]
+    fn trial_resumes_across_frames() {
+        let config: LevelConfig =
+            ron::from_str(include_str!("../assets/levels/level1.ron")).unwrap();
+        let (whole, _) = fly_trial(&config, usize::MAX);
+        let (split, frames) = fly_trial(&config, 1000);
+
+        assert!(frames > 1, "the trial should have spanned several frames");
+        assert!(matches!(whole.outcome, RunOutcome::TimedOut));
+        assert!(matches!(split.outcome, RunOutcome::TimedOut));
+        assert_eq!(whole.fuel_remaining, split.fuel_remaining);
+        assert_eq!(whole.touchdown_speed, split.touchdown_speed);
+    }
+}
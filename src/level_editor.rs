@@ -0,0 +1,814 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use bevy_persistent::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::FRAC_PI_2;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+use crate::assets::ScriptAsset;
+use crate::levels::{
+    BoundingBox, CollisionShape, ControlScheme, CurrentLevel, Dispersion, Integrator, LevelConfig,
+    LevelManager, Overheat, Reference, RoughTerrain, Stage, Turbulence, Wind, CUSTOM_LEVEL_START,
+};
+use crate::persistence::LevelProgress;
+use crate::rhai_api::ScriptEngine;
+use crate::simulation::{reset_simulation, LanderState, Trajectory};
+use crate::ui::{switch_level, EditorState, GameState};
+use crate::visualization::{
+    CameraState, ResetVisibilityFlag, ResetVisualization, RIGHT_PANEL_WIDTH,
+};
+
+// Levels made in the level editor, saved apart from the progress so they can be edited
+// or shared as RON
+#[derive(Resource, Serialize, Deserialize, Clone, Default)]
+pub struct CustomLevels {
+    pub levels: Vec<LevelConfig>,
+}
+
+// Level being edited and where it's saved
+#[derive(Resource, Default)]
+pub struct LevelEditorState {
+    config: Option<LevelConfig>,
+    index: Option<usize>, // Position in CustomLevels once saved
+    ron_text: String,     // Text of the whole level for the fields the form doesn't cover
+    message: Option<String>,
+}
+
+impl LevelEditorState {
+    // Edit a copy of a level; `index` is its slot in CustomLevels, None for a new level
+    pub fn open(&mut self, config: LevelConfig, index: Option<usize>) {
+        self.ron_text = to_ron(&config);
+        self.config = Some(config);
+        self.index = index;
+        self.message = None;
+    }
+}
+
+fn to_ron(config: &LevelConfig) -> String {
+    ron::ser::to_string_pretty(config, ron::ser::PrettyConfig::default()).unwrap_or_default()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn custom_levels_path() -> PathBuf {
+    PathBuf::from("local").join("gnc-trainer-custom-levels")
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn custom_levels_path() -> PathBuf {
+    let config_dir = dirs::config_dir()
+        .map(|native_config_dir| native_config_dir.join("lander-game"))
+        .unwrap_or(PathBuf::from("local/configuration"));
+    config_dir.join("custom_levels.ron")
+}
+
+// Load the saved custom levels and add them to the level list
+pub fn setup_custom_levels(mut commands: Commands, mut level_manager: ResMut<LevelManager>) {
+    let custom_levels = Persistent::<CustomLevels>::builder()
+        .name("custom_levels")
+        .format(StorageFormat::RonPretty)
+        .path(custom_levels_path())
+        .default(CustomLevels::default())
+        .revertible(true)
+        .build()
+        .expect("Failed to initialize custom levels");
+    level_manager.set_custom_levels(&custom_levels.levels);
+    commands.insert_resource(custom_levels);
+}
+
+// Show the level being edited in the visualization behind the form
+#[allow(clippy::too_many_arguments)]
+fn preview(
+    config: &LevelConfig,
+    current_level: &mut CurrentLevel,
+    lander_state: &mut LanderState,
+    camera_state: &mut CameraState,
+    trajectory: &mut Trajectory,
+    script_engine: &mut ScriptEngine,
+    reset_flag: &mut ResetVisibilityFlag,
+    reset_vis: &mut ResetVisualization,
+) {
+    current_level.config = config.clone();
+    reset_simulation(
+        lander_state,
+        current_level,
+        camera_state,
+        trajectory,
+        script_engine,
+    );
+    reset_flag.0 = true;
+    reset_vis.0 = true;
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn enter_level_editor(
+    level_editor: Res<LevelEditorState>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut lander_state: ResMut<LanderState>,
+    mut camera_state: ResMut<CameraState>,
+    mut trajectory: ResMut<Trajectory>,
+    mut script_engine: ResMut<ScriptEngine>,
+    mut reset_flag: ResMut<ResetVisibilityFlag>,
+    mut reset_vis: ResMut<ResetVisualization>,
+) {
+    if let Some(config) = &level_editor.config {
+        preview(
+            config,
+            &mut current_level,
+            &mut lander_state,
+            &mut camera_state,
+            &mut trajectory,
+            &mut script_engine,
+            &mut reset_flag,
+            &mut reset_vis,
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn level_editor_ui(
+    mut contexts: EguiContexts,
+    mut level_editor: ResMut<LevelEditorState>,
+    mut custom_levels: ResMut<Persistent<CustomLevels>>,
+    mut level_manager: ResMut<LevelManager>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut lander_state: ResMut<LanderState>,
+    mut camera_state: ResMut<CameraState>,
+    mut trajectory: ResMut<Trajectory>,
+    mut script_engine: ResMut<ScriptEngine>,
+    (mut reset_flag, mut reset_vis): (ResMut<ResetVisibilityFlag>, ResMut<ResetVisualization>),
+    mut state: ResMut<NextState<GameState>>,
+    mut editor_state: ResMut<EditorState>,
+    progress: Res<Persistent<LevelProgress>>,
+    asset_server: Res<AssetServer>,
+    script_assets: Res<Assets<ScriptAsset>>,
+) {
+    let LevelEditorState {
+        config,
+        index,
+        ron_text,
+        message,
+    } = &mut *level_editor;
+    let Some(config) = config.as_mut() else {
+        state.set(GameState::LevelSelect);
+        return;
+    };
+
+    let mut changed = false;
+    let mut save = false;
+    let mut test = false;
+    let mut back = false;
+
+    egui::SidePanel::right("level_editor")
+        .exact_width(RIGHT_PANEL_WIDTH)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.heading("Level Editor");
+            ui.horizontal(|ui| {
+                save = ui.button("Save").clicked();
+                test = ui
+                    .button("Test")
+                    .on_hover_text("Save the level and fly it")
+                    .clicked();
+                back = ui.button("Back").clicked();
+            });
+            if let Some(message) = message.as_ref() {
+                ui.label(message.as_str());
+            }
+            ui.separator();
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                changed |= general_section(ui, config);
+                changed |= physics_section(ui, config);
+                changed |= initial_section(ui, config);
+                changed |= success_section(ui, config);
+                changed |= failure_section(ui, config);
+                changed |= environment_section(ui, config);
+
+                // Everything else (terrain, waypoints, obstacles, objectives...) as RON
+                ui.collapsing("All fields (RON)", |ui| {
+                    ui.label("Terrain, waypoints, obstacles and the rest of the level.");
+                    ui.add(
+                        egui::TextEdit::multiline(ron_text)
+                            .code_editor()
+                            .desired_rows(16)
+                            .desired_width(f32::INFINITY),
+                    );
+                    if ui.button("Apply").clicked() {
+                        match ron::de::from_str::<LevelConfig>(ron_text) {
+                            Ok(parsed) => {
+                                *config = parsed;
+                                *message = None;
+                                changed = true;
+                            }
+                            Err(error) => *message = Some(format!("Invalid level: {}", error)),
+                        }
+                    }
+                });
+            });
+        });
+
+    if changed {
+        *ron_text = to_ron(config);
+        preview(
+            config,
+            &mut current_level,
+            &mut lander_state,
+            &mut camera_state,
+            &mut trajectory,
+            &mut script_engine,
+            &mut reset_flag,
+            &mut reset_vis,
+        );
+    }
+
+    if save || test {
+        // Levels are looked up by name, so it has to be unique
+        let taken = level_manager.available_levels.iter().any(|(number, name)| {
+            name == &config.name && Some(*number) != index.map(|i| CUSTOM_LEVEL_START + i)
+        });
+        if config.name.trim().is_empty() {
+            *message = Some("The level needs a name.".into());
+            return;
+        }
+        if taken {
+            *message = Some(format!(
+                "There is already a level called \"{}\".",
+                config.name
+            ));
+            return;
+        }
+        if let Err(error) = config.validate() {
+            *message = Some(format!("Can't save the level: {}.", error));
+            return;
+        }
+
+        let saved = config.clone();
+        let slot = *index;
+        let result = custom_levels.update(|custom| match slot {
+            Some(i) if i < custom.levels.len() => custom.levels[i] = saved.clone(),
+            _ => custom.levels.push(saved.clone()),
+        });
+        if let Err(error) = result {
+            *message = Some(format!("Couldn't save the level: {}", error));
+            return;
+        }
+        let i = slot.unwrap_or(custom_levels.levels.len() - 1);
+        *index = Some(i);
+        level_manager.set_custom_levels(&custom_levels.levels);
+
+        let twr = config.initial_thrust_to_weight();
+        *message = Some(if twr < 1.0 {
+            format!(
+                "Saved. Warning: the thrust-to-weight ratio is {:.2}, so the lander can't hover.",
+                twr
+            )
+        } else {
+            "Saved.".into()
+        });
+
+        if test {
+            switch_level(
+                CUSTOM_LEVEL_START + i,
+                config.clone(),
+                &level_manager,
+                &mut editor_state,
+                &mut current_level,
+                &mut script_engine,
+                &progress,
+                &asset_server,
+                &script_assets,
+            );
+            reset_simulation(
+                &mut lander_state,
+                &current_level,
+                &mut camera_state,
+                &mut trajectory,
+                &mut script_engine,
+            );
+            reset_flag.0 = true;
+            reset_vis.0 = true;
+            state.set(GameState::Playing);
+        }
+    }
+
+    if back {
+        state.set(GameState::LevelSelect);
+    }
+}
+
+// One labelled number in a two-column grid
+fn number(ui: &mut egui::Ui, label: &str, value: &mut f32, speed: f64) -> bool {
+    ui.label(label);
+    let changed = ui.add(egui::DragValue::new(value).speed(speed)).changed();
+    ui.end_row();
+    changed
+}
+
+// Number the drag can't take outside `range`
+fn bounded_number(
+    ui: &mut egui::Ui,
+    label: &str,
+    value: &mut f32,
+    speed: f64,
+    range: RangeInclusive<f32>,
+) -> bool {
+    ui.label(label);
+    let changed = ui
+        .add(egui::DragValue::new(value).speed(speed).range(range))
+        .changed();
+    ui.end_row();
+    changed
+}
+
+// Smallest value the editor lets a mass, Isp, inertia or rate go down to
+const MIN_POSITIVE: f32 = 0.001;
+
+fn text(ui: &mut egui::Ui, label: &str, value: &mut String, multiline: bool) -> bool {
+    ui.label(label);
+    let changed = if multiline {
+        ui.text_edit_multiline(value).changed()
+    } else {
+        ui.text_edit_singleline(value).changed()
+    };
+    ui.end_row();
+    changed
+}
+
+fn flag(ui: &mut egui::Ui, label: &str, value: &mut bool) -> bool {
+    ui.label(label);
+    let changed = ui.checkbox(value, "").changed();
+    ui.end_row();
+    changed
+}
+
+// Number that can be switched off, e.g. an optional limit
+fn optional_number(
+    ui: &mut egui::Ui,
+    label: &str,
+    value: &mut Option<f32>,
+    default: f32,
+    speed: f64,
+) -> bool {
+    ui.label(label);
+    let changed = ui
+        .horizontal(|ui| {
+            let mut enabled = value.is_some();
+            let mut changed = ui.checkbox(&mut enabled, "").changed();
+            if changed {
+                *value = enabled.then_some(default);
+            }
+            if let Some(value) = value {
+                changed |= ui.add(egui::DragValue::new(value).speed(speed)).changed();
+            }
+            changed
+        })
+        .inner;
+    ui.end_row();
+    changed
+}
+
+// Collapsible section laid out as a label/value grid
+fn section(ui: &mut egui::Ui, title: &str, body: impl FnOnce(&mut egui::Ui) -> bool) -> bool {
+    ui.collapsing(title, |ui| {
+        egui::Grid::new(title)
+            .num_columns(2)
+            .striped(true)
+            .show(ui, body)
+            .inner
+    })
+    .body_returned
+    .unwrap_or(false)
+}
+
+// Optional part of the level, with a checkbox that adds or removes it
+fn optional_section<T>(
+    ui: &mut egui::Ui,
+    title: &str,
+    value: &mut Option<T>,
+    make: impl FnOnce() -> T,
+    body: impl FnOnce(&mut egui::Ui, &mut T) -> bool,
+) -> bool {
+    let mut enabled = value.is_some();
+    let mut changed = ui.checkbox(&mut enabled, title).changed();
+    if changed {
+        *value = enabled.then(make);
+    }
+    if let Some(value) = value {
+        ui.indent(title, |ui| {
+            egui::Grid::new(title).num_columns(2).show(ui, |ui| {
+                changed |= body(ui, value);
+            });
+        });
+    }
+    changed
+}
+
+fn bounding_box(ui: &mut egui::Ui, bounds: &mut BoundingBox) -> bool {
+    let mut changed = number(ui, "x min (m)", &mut bounds.x_min, 1.0);
+    changed |= number(ui, "x max (m)", &mut bounds.x_max, 1.0);
+    changed |= number(ui, "y min (m)", &mut bounds.y_min, 1.0);
+    changed |= number(ui, "y max (m)", &mut bounds.y_max, 1.0);
+    ui.label("Relative to");
+    egui::ComboBox::from_id_salt(ui.next_auto_id())
+        .selected_text(match bounds.reference {
+            Reference::Absolute => "World origin",
+            Reference::Initial => "Start position",
+        })
+        .show_ui(ui, |ui| {
+            changed |= ui
+                .selectable_value(&mut bounds.reference, Reference::Absolute, "World origin")
+                .changed();
+            changed |= ui
+                .selectable_value(&mut bounds.reference, Reference::Initial, "Start position")
+                .changed();
+        });
+    ui.end_row();
+    changed
+}
+
+fn general_section(ui: &mut egui::Ui, config: &mut LevelConfig) -> bool {
+    section(ui, "General", |ui| {
+        let mut changed = text(ui, "Name", &mut config.name, false);
+        changed |= text(ui, "Description", &mut config.description, true);
+        changed |= text(ui, "Hint", &mut config.hint, true);
+        changed |= text(ui, "Success message", &mut config.success_message, false);
+        changed |= text(ui, "Failure message", &mut config.failure_message, false);
+
+        ui.label("Control scheme");
+        egui::ComboBox::from_id_salt("control_scheme")
+            .selected_text(format!("{:?}", config.control_scheme))
+            .show_ui(ui, |ui| {
+                for scheme in [
+                    ControlScheme::VerticalOnly,
+                    ControlScheme::ThrustVector,
+                    ControlScheme::Translate,
+                ] {
+                    let label = format!("{:?}", scheme);
+                    changed |= ui
+                        .selectable_value(&mut config.control_scheme, scheme, label)
+                        .changed();
+                }
+            });
+        ui.end_row();
+
+        changed |= number(
+            ui,
+            "Low fuel warning (fraction)",
+            &mut config.low_fuel_fraction,
+            0.01,
+        );
+        changed |= optional_number(ui, "Sensor rate (Hz)", &mut config.sensor_rate, 10.0, 0.5);
+        changed |= flag(ui, "Show grid", &mut config.show_grid);
+        changed |= optional_number(ui, "Grid spacing (m)", &mut config.grid_spacing, 10.0, 1.0);
+        changed |= flag(ui, "Success checklist", &mut config.guided);
+        changed
+    })
+}
+
+fn physics_section(ui: &mut egui::Ui, config: &mut LevelConfig) -> bool {
+    let physics = &mut config.physics;
+    let mut changed = section(ui, "Physics", |ui| {
+        let mut changed = number(ui, "Gravity (m/s²)", &mut physics.gravity, 0.1);
+        changed |= bounded_number(
+            ui,
+            "Dry mass (kg)",
+            &mut physics.dry_mass,
+            1.0,
+            MIN_POSITIVE..=f32::MAX,
+        );
+        changed |= number(ui, "Max thrust (N)", &mut physics.max_thrust, 10.0);
+        changed |= bounded_number(
+            ui,
+            "Specific impulse (s)",
+            &mut physics.isp,
+            1.0,
+            MIN_POSITIVE..=f32::MAX,
+        );
+        changed |= number(
+            ui,
+            "Lateral thrust (N)",
+            &mut physics.max_lateral_thrust,
+            10.0,
+        );
+        changed |= bounded_number(
+            ui,
+            "Moment of inertia (kg·m²)",
+            &mut physics.moment_of_inertia,
+            1.0,
+            MIN_POSITIVE..=f32::MAX,
+        );
+        changed |= number(
+            ui,
+            "Thrust moment arm (m)",
+            &mut physics.thrust_moment_arm,
+            0.1,
+        );
+        changed |= number(ui, "Center of mass shift (m)", &mut physics.com_shift, 0.1);
+        changed |= number(ui, "Drag coefficient", &mut physics.drag_coefficient, 0.01);
+        changed |= number(ui, "Air density (kg/m³)", &mut physics.air_density, 0.01);
+        changed |= bounded_number(
+            ui,
+            "Throttle rate (1/s)",
+            &mut physics.thrust_rate,
+            0.1,
+            MIN_POSITIVE..=f32::MAX,
+        );
+        changed |= number(ui, "Gimbal rate (rad/s)", &mut physics.gimbal_rate, 0.1);
+        changed |= bounded_number(
+            ui,
+            "Gimbal min (rad)",
+            &mut physics.gimbal_min,
+            0.01,
+            -FRAC_PI_2..=0.0,
+        );
+        changed |= bounded_number(
+            ui,
+            "Gimbal max (rad)",
+            &mut physics.gimbal_max,
+            0.01,
+            0.0..=FRAC_PI_2,
+        );
+        changed |= number(ui, "Startup delay (s)", &mut physics.startup_delay, 0.1);
+        changed |= number(ui, "Min throttle", &mut physics.min_throttle, 0.01);
+        changed |= number(ui, "RCS torque (N·m)", &mut physics.rcs_max_torque, 1.0);
+        changed |= number(ui, "RCS fuel (kg)", &mut physics.rcs_fuel, 0.1);
+        changed |= number(ui, "RCS fuel rate (kg/s)", &mut physics.rcs_fuel_rate, 0.01);
+        changed |= number(ui, "Leg stiffness (N/m)", &mut physics.leg_stiffness, 100.0);
+        changed |= number(ui, "Leg damping (N·s/m)", &mut physics.leg_damping, 10.0);
+        changed |= number(ui, "Leg stroke (m)", &mut physics.leg_stroke, 0.01);
+        changed |= optional_number(
+            ui,
+            "Planet radius (m)",
+            &mut physics.planet_radius,
+            1000.0,
+            10.0,
+        );
+
+        ui.label("Integrator");
+        egui::ComboBox::from_id_salt("integrator")
+            .selected_text(format!("{:?}", physics.integrator))
+            .show_ui(ui, |ui| {
+                for integrator in [Integrator::Euler, Integrator::Rk4] {
+                    let label = format!("{:?}", integrator);
+                    changed |= ui
+                        .selectable_value(&mut physics.integrator, integrator, label)
+                        .changed();
+                }
+            });
+        ui.end_row();
+        changed
+    });
+
+    ui.indent("physics_options", |ui| {
+        changed |= optional_section(
+            ui,
+            "Engine overheating",
+            &mut physics.overheat,
+            || Overheat {
+                throttle_threshold: 0.8,
+                heating_rate: 1.0,
+                cooling_rate: 1.0,
+                max_heat: 10.0,
+                fail_on_overheat: false,
+            },
+            |ui, overheat| {
+                let mut changed = number(
+                    ui,
+                    "Throttle threshold",
+                    &mut overheat.throttle_threshold,
+                    0.01,
+                );
+                changed |= number(ui, "Heating rate", &mut overheat.heating_rate, 0.1);
+                changed |= number(ui, "Cooling rate", &mut overheat.cooling_rate, 0.1);
+                changed |= number(ui, "Max heat", &mut overheat.max_heat, 0.1);
+                changed |= flag(ui, "Fail on overheat", &mut overheat.fail_on_overheat);
+                changed
+            },
+        );
+        changed |= optional_section(
+            ui,
+            "Droppable stage",
+            &mut physics.stage,
+            || Stage {
+                dry_mass: 50.0,
+                fuel: 20.0,
+            },
+            |ui, stage| {
+                let mut changed = number(ui, "Stage dry mass (kg)", &mut stage.dry_mass, 1.0);
+                changed |= number(ui, "Stage fuel (kg)", &mut stage.fuel, 1.0);
+                changed
+            },
+        );
+        changed |= optional_section(
+            ui,
+            "Turbulence",
+            &mut physics.turbulence,
+            || Turbulence {
+                amplitude: 50.0,
+                correlation_time: 2.0,
+            },
+            |ui, turbulence| {
+                let mut changed = number(ui, "Amplitude (N)", &mut turbulence.amplitude, 1.0);
+                changed |= number(
+                    ui,
+                    "Correlation time (s)",
+                    &mut turbulence.correlation_time,
+                    0.1,
+                );
+                changed
+            },
+        );
+    });
+    changed
+}
+
+fn initial_section(ui: &mut egui::Ui, config: &mut LevelConfig) -> bool {
+    let initial = &mut config.initial;
+    section(ui, "Initial state", |ui| {
+        let mut changed = number(ui, "x (m)", &mut initial.x0, 1.0);
+        changed |= number(ui, "Altitude (m)", &mut initial.y0, 1.0);
+        changed |= number(ui, "vx (m/s)", &mut initial.vx0, 0.1);
+        changed |= number(ui, "vy (m/s)", &mut initial.vy0, 0.1);
+        changed |= number(ui, "Angle (rad)", &mut initial.initial_angle, 0.01);
+        changed |= number(
+            ui,
+            "Angular velocity (rad/s)",
+            &mut initial.initial_angular_vel,
+            0.01,
+        );
+        changed |= number(ui, "Fuel (kg)", &mut initial.initial_fuel, 1.0);
+        changed |= number(ui, "Throttle", &mut initial.initial_thrust, 0.01);
+        changed
+    })
+}
+
+fn success_section(ui: &mut egui::Ui, config: &mut LevelConfig) -> bool {
+    let success = &mut config.success;
+    section(ui, "Success criteria", |ui| {
+        let mut changed = number(ui, "Max vx (m/s)", &mut success.vx_max, 0.1);
+        changed |= number(ui, "Max vy (m/s)", &mut success.vy_max, 0.1);
+        changed |= number(ui, "Final angle (rad)", &mut success.final_angle, 0.01);
+        changed |= number(
+            ui,
+            "Angle tolerance (rad)",
+            &mut success.angle_tolerance,
+            0.01,
+        );
+        changed |= number(ui, "Hold time (s)", &mut success.persistence_period, 0.1);
+        changed |= number(
+            ui,
+            "Max touchdown tilt (rad)",
+            &mut success.max_touchdown_tilt,
+            0.01,
+        );
+        changed |= number(
+            ui,
+            "Max lateral speed (m/s)",
+            &mut success.max_lateral_speed,
+            0.1,
+        );
+        changed |= optional_number(
+            ui,
+            "Max velocity angle (rad)",
+            &mut success.max_velocity_angle,
+            0.2,
+            0.01,
+        );
+        changed |= optional_number(
+            ui,
+            "Max angular rate (rad/s)",
+            &mut success.max_angular_rate,
+            0.1,
+            0.01,
+        );
+        ui.label("Landing box");
+        ui.end_row();
+        changed |= bounding_box(ui, &mut success.position_box);
+        changed
+    })
+}
+
+fn failure_section(ui: &mut egui::Ui, config: &mut LevelConfig) -> bool {
+    let failure = &mut config.failure;
+    let mut changed = section(ui, "Failure criteria", |ui| {
+        let mut changed = flag(
+            ui,
+            "Any ground contact fails",
+            &mut failure.ground_collision,
+        );
+        ui.label("Collision shape");
+        egui::ComboBox::from_id_salt("collision_shape")
+            .selected_text(format!("{:?}", failure.collision_shape))
+            .show_ui(ui, |ui| {
+                for shape in [CollisionShape::Point, CollisionShape::Footprint] {
+                    let label = format!("{:?}", shape);
+                    changed |= ui
+                        .selectable_value(&mut failure.collision_shape, shape, label)
+                        .changed();
+                }
+            });
+        ui.end_row();
+        changed
+    });
+    ui.indent("failure_options", |ui| {
+        changed |= optional_section(
+            ui,
+            "Out-of-bounds box",
+            &mut failure.bounds,
+            || BoundingBox {
+                x_min: -500.0,
+                x_max: 500.0,
+                y_min: -10.0,
+                y_max: 1000.0,
+                reference: Reference::Absolute,
+            },
+            bounding_box,
+        );
+    });
+    changed
+}
+
+fn environment_section(ui: &mut egui::Ui, config: &mut LevelConfig) -> bool {
+    let mut changed = false;
+    ui.collapsing("Environment", |ui| {
+        changed |= optional_section(
+            ui,
+            "Wind",
+            &mut config.wind,
+            || Wind {
+                base: 20.0,
+                gust_amplitude: 10.0,
+                gust_period: 10.0,
+            },
+            |ui, wind| {
+                let mut changed = number(ui, "Steady force (N)", &mut wind.base, 1.0);
+                changed |= number(ui, "Gust amplitude (N)", &mut wind.gust_amplitude, 1.0);
+                changed |= number(ui, "Gust period (s)", &mut wind.gust_period, 0.1);
+                changed
+            },
+        );
+        changed |= optional_section(
+            ui,
+            "Rough terrain",
+            &mut config.rough_terrain,
+            || RoughTerrain {
+                max_bump_velocity: 1.0,
+                max_bump_angular_vel: 0.2,
+            },
+            |ui, rough| {
+                let mut changed = number(
+                    ui,
+                    "Max bump velocity (m/s)",
+                    &mut rough.max_bump_velocity,
+                    0.1,
+                );
+                changed |= number(
+                    ui,
+                    "Max bump spin (rad/s)",
+                    &mut rough.max_bump_angular_vel,
+                    0.01,
+                );
+                changed
+            },
+        );
+        changed |= optional_section(
+            ui,
+            "Initial state dispersion",
+            &mut config.dispersion,
+            Dispersion::default,
+            |ui, dispersion| {
+                let mut changed = number(ui, "x σ (m)", &mut dispersion.x0, 0.1);
+                changed |= number(ui, "vx σ (m/s)", &mut dispersion.vx0, 0.1);
+                changed |= number(ui, "vy σ (m/s)", &mut dispersion.vy0, 0.1);
+                changed |= number(ui, "Angle σ (rad)", &mut dispersion.initial_angle, 0.01);
+                changed
+            },
+        );
+        ui.label("Exhaust");
+        ui.indent("visuals", |ui| {
+            egui::Grid::new("visuals").num_columns(2).show(ui, |ui| {
+                let visuals = &mut config.visuals;
+                // The level stores sRGB components, which the egui picker edits as bytes
+                ui.label("Color");
+                let mut srgba = visuals
+                    .exhaust_color
+                    .map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+                if ui
+                    .color_edit_button_srgba_unmultiplied(&mut srgba)
+                    .changed()
+                {
+                    visuals.exhaust_color = srgba.map(|c| c as f32 / 255.0);
+                    changed = true;
+                }
+                ui.end_row();
+                changed |= number(
+                    ui,
+                    "Particle intensity",
+                    &mut visuals.particle_intensity,
+                    0.05,
+                );
+            });
+        });
+    });
+    changed
+}
@@ -1,24 +1,185 @@
 use bevy::prelude::*;
 use bevy::utils::hashbrown::HashMap;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::assets::{RonAsset, RonAssetLoader};
+use crate::constants::LANDER_BASE_OFFSET;
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
 pub enum ControlScheme {
     VerticalOnly,
     ThrustVector,
+    Translate, // vertical thrust plus lateral RCS thrust, no rotation
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Physics {
     pub gravity: f32,    // gravity acceleration (m/s²)
     pub dry_mass: f32,   // dry mass of the lander (kg)
     pub max_thrust: f32, // maximum thrust force (N)
     pub isp: f32,        // specific impulse (s)
+    #[serde(default)]
+    pub max_lateral_thrust: f32, // lateral RCS thrust for the Translate scheme (N)
+    #[serde(default)]
+    pub overheat: Option<Overheat>, // Optional engine bell thermal model
+    #[serde(default)]
+    pub planet_radius: Option<f32>, // Gravity points at a center this far below the origin (m)
+    #[serde(default)]
+    pub gravity_model: GravityModel, // How gravity varies with altitude in flat-ground levels
+    #[serde(default)]
+    pub integrator: Integrator, // Numerical integration method for the equations of motion
+    #[serde(default)]
+    pub stage: Option<Stage>, // Droppable stage the script can jettison()
+    #[serde(default)]
+    pub turbulence: Option<Turbulence>, // Continuous random wind disturbance
+    #[serde(default = "default_moment_of_inertia")]
+    pub moment_of_inertia: f32, // rotational inertia about the center of mass (kg·m²), fully fuelled
+    #[serde(default = "default_thrust_moment_arm")]
+    pub thrust_moment_arm: f32, // distance from the center of mass to the gimbal (m), fully fuelled
+    #[serde(default)]
+    pub drag_coefficient: f32, // aerodynamic drag coefficient, 0 for no atmosphere
+    #[serde(default)]
+    pub air_density: f32, // atmospheric density (kg/m³)
+    #[serde(default)]
+    pub com_shift: f32, // how far the center of mass moves away from the gimbal as the tanks empty (m)
+    #[serde(default = "default_thrust_rate")]
+    pub thrust_rate: f32, // fastest throttle change, also used by the lateral thrusters (1/s)
+    #[serde(default = "default_gimbal_rate")]
+    pub gimbal_rate: f32, // fastest gimbal swing (rad/s)
+    #[serde(default = "default_gimbal_min")]
+    pub gimbal_min: f32, // gimbal travel (radians)
+    #[serde(default = "default_gimbal_max")]
+    pub gimbal_max: f32,
+    #[serde(default)]
+    pub startup_delay: f32, // time from commanding thrust to the engine producing any (s)
+    #[serde(default)]
+    pub min_throttle: f32, // lowest throttle the engine runs at; below this it's off (0 to 1)
+    #[serde(default)]
+    pub rcs_max_torque: f32, // attitude thrusters' torque at full command, 0 for none (N·m)
+    #[serde(default)]
+    pub rcs_fuel: f32, // separate attitude thruster propellant, not counted in the mass (kg)
+    #[serde(default)]
+    pub rcs_fuel_rate: f32, // attitude propellant use at full command (kg/s)
+    #[serde(default)]
+    pub leg_stiffness: f32, // landing leg spring rate, 0 for rigid legs that stop the lander dead (N/m)
+    #[serde(default)]
+    pub leg_damping: f32, // landing leg damper rate, also the ground friction on sliding (N·s/m)
+    #[serde(default = "default_leg_stroke")]
+    pub leg_stroke: f32, // furthest the legs compress; bottoming them out is a crash (m)
 }
 
-#[derive(Debug, Deserialize, Clone)]
+fn default_leg_stroke() -> f32 {
+    0.5
+}
+
+fn default_moment_of_inertia() -> f32 {
+    100.0
+}
+
+fn default_thrust_rate() -> f32 {
+    2.0
+}
+
+fn default_gimbal_rate() -> f32 {
+    1.0
+}
+
+fn default_gimbal_min() -> f32 {
+    -0.4
+}
+
+fn default_gimbal_max() -> f32 {
+    0.4
+}
+
+fn default_thrust_moment_arm() -> f32 {
+    LANDER_BASE_OFFSET
+}
+
+// Smoothly varying disturbance force drawn from seeded noise, so a run's seed
+// always gives the same gusts
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Turbulence {
+    pub amplitude: f32,        // largest disturbance force along each axis (N)
+    pub correlation_time: f32, // time over which the force changes noticeably (s)
+}
+
+// Part of the lander that separates on jettison(). Its mass is included in the
+// lander's dry mass and initial fuel until then.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Stage {
+    pub dry_mass: f32, // structure mass dropped with the stage (kg)
+    pub fuel: f32,     // fuel in the stage's tanks, lost with it (kg)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum GravityModel {
+    #[default]
+    Constant, // Same gravity at every altitude
+    Inverse {
+        surface_radius: f32, // planet radius for the inverse-square falloff (m)
+    }, // Falls off with the square of the distance from the planet center, still straight down
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum Integrator {
+    #[default]
+    Euler, // Single explicit step per frame
+    Rk4, // Fourth-order Runge-Kutta, forces re-evaluated at the midpoints
+}
+
+impl Physics {
+    // Center of the planet, or None for flat, straight-down gravity
+    pub fn planet_center(&self) -> Option<Vec2> {
+        self.planet_radius.map(|radius| Vec2::new(0.0, -radius))
+    }
+
+    // Unit vector pointing away from the ground at a position
+    pub fn local_up(&self, position: Vec2) -> Vec2 {
+        match self.planet_center() {
+            Some(center) => (position - center).normalize_or(Vec2::Y),
+            None => Vec2::Y,
+        }
+    }
+
+    // Height above the surface, measured radially in planet-center mode
+    pub fn altitude(&self, position: Vec2) -> f32 {
+        match (self.planet_center(), self.planet_radius) {
+            (Some(center), Some(radius)) => position.distance(center) - radius,
+            _ => position.y,
+        }
+    }
+
+    // Gravity acceleration vector at a position. In planet-center mode the magnitude
+    // falls off with the square of the distance, matching `gravity` at the surface.
+    // Flat-ground levels can fall off the same way with altitude but stay vertical.
+    pub fn gravity_at(&self, position: Vec2) -> Vec2 {
+        match (self.planet_center(), self.planet_radius) {
+            (Some(center), Some(radius)) => {
+                let distance = position.distance(center).max(f32::EPSILON);
+                self.local_up(position) * self.gravity * (radius / distance).powi(2)
+            }
+            _ => match self.gravity_model {
+                GravityModel::Constant => Vec2::new(0.0, self.gravity),
+                GravityModel::Inverse { surface_radius } => {
+                    let distance = (surface_radius + position.y).max(f32::EPSILON);
+                    Vec2::new(0.0, self.gravity * (surface_radius / distance).powi(2))
+                }
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Overheat {
+    pub throttle_threshold: f32, // throttle above which the engine bell heats up (0 to 1)
+    pub heating_rate: f32,       // heat gained per second per unit of throttle above threshold
+    pub cooling_rate: f32,       // heat shed per second per unit of throttle below threshold
+    pub max_heat: f32,           // heat at which the engine overheats
+    pub fail_on_overheat: bool,  // fail the run instead of temporarily cutting the engine
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct InitialState {
     pub x0: f32,            // initial horizontal position
     pub y0: f32,            // initial altitude
@@ -26,15 +187,19 @@ pub struct InitialState {
     pub vy0: f32,           // initial vertical velocity
     pub initial_angle: f32, // initial rotation (radians)
     pub initial_fuel: f32,  // initial fuel mass (kg)
+    #[serde(default)]
+    pub initial_thrust: f32, // throttle the engine is already running at (0.0 to 1.0)
+    #[serde(default)]
+    pub initial_angular_vel: f32, // initial angular velocity (rad/s)
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum Reference {
     Absolute, // Compare against absolute coordinates
     Initial,  // Compare against initial state
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BoundingBox {
     pub x_min: f32,
     pub x_max: f32,
@@ -43,7 +208,7 @@ pub struct BoundingBox {
     pub reference: Reference,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SuccessCriteria {
     pub vx_max: f32,               // max horizontal velocity
     pub vy_max: f32,               // max vertical velocity
@@ -51,15 +216,54 @@ pub struct SuccessCriteria {
     pub final_angle: f32,          // desired final angle (radians)
     pub angle_tolerance: f32,      // acceptable deviation from final angle (radians)
     pub persistence_period: f32,   // time criteria must be met (seconds)
+    #[serde(default)]
+    pub max_velocity_angle: Option<f32>, // max angle of the touchdown velocity from straight down (radians)
+    #[serde(default)]
+    pub max_angular_rate: Option<f32>, // max angular velocity (rad/s)
+    #[serde(default)]
+    pub objective: Objective,
+    #[serde(default = "default_max_touchdown_tilt")]
+    pub max_touchdown_tilt: f32, // tilt from the local vertical beyond which a touchdown tips over (radians)
+    #[serde(default = "default_max_lateral_speed")]
+    pub max_lateral_speed: f32, // sideways speed beyond which a touchdown tips over (m/s)
+}
+
+fn default_max_touchdown_tilt() -> f32 {
+    0.5
+}
+
+fn default_max_lateral_speed() -> f32 {
+    5.0
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub enum Objective {
+    #[default]
+    Landing, // Meet the criteria inside the position box (on the ground for Absolute boxes)
+    Ascent {
+        target_altitude: f32,    // altitude to reach (m)
+        altitude_tolerance: f32, // acceptable distance from the target altitude (m)
+    }, // Reach the altitude in flight; the position box only limits x
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum CollisionShape {
+    #[default]
+    Point, // Test only the lander's base point below its center
+    Footprint, // Test all corners of the (rotated) lander triangle
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FailureCriteria {
     pub ground_collision: bool, // whether ground collision is an instant fail
     pub bounds: Option<BoundingBox>, // Optional out-of-bounds box that causes failure
+    #[serde(default)]
+    pub collision_shape: CollisionShape, // Shape used for ground/bounds collision tests
+    #[serde(default)]
+    pub obstacles: Vec<BoundingBox>, // No-fly boxes; touching one with any part of the lander fails
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LevelConfig {
     pub name: String,
     pub description: String,
@@ -71,6 +275,300 @@ pub struct LevelConfig {
     pub control_scheme: ControlScheme,
     pub success_message: String,
     pub failure_message: String,
+    #[serde(default = "default_low_fuel_fraction")]
+    pub low_fuel_fraction: f32, // fraction of initial fuel that triggers the low-fuel warning (0 disables)
+    #[serde(default)]
+    pub rough_terrain: Option<RoughTerrain>, // Random bump applied at first ground contact
+    #[serde(default)]
+    pub sensor_rate: Option<f32>, // Hz at which the script's position/velocity/attitude update (None = every step)
+    #[serde(default = "default_show_grid")]
+    pub show_grid: bool,
+    #[serde(default)]
+    pub grid_spacing: Option<f32>, // Grid line spacing (m), defaults to 10
+    #[serde(default)]
+    pub guided: bool, // Show a live checklist of the success criteria
+    #[serde(default)]
+    pub wind: Option<Wind>, // Horizontal wind force, steady plus a periodic gust
+    #[serde(default)]
+    pub dispersion: Option<Dispersion>, // Random spread of the initial state, when enabled
+    #[serde(default)]
+    pub waypoints: Vec<Waypoint>, // Points to pass through in order before the success box counts
+    #[serde(default)]
+    pub terrain: Option<Terrain>, // Ground profile instead of flat ground at y=0 (flat-ground levels only)
+    #[serde(default)]
+    pub visuals: Visuals, // Look of the engine exhaust
+}
+
+// Ground profile through (x, height) points, straight between them and level beyond
+// the first and last one
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Terrain {
+    #[serde(deserialize_with = "deserialize_terrain_points")]
+    pub points: Vec<(f32, f32)>, // (x, ground height) in meters, in increasing x
+    #[serde(default = "default_max_landing_slope")]
+    pub max_landing_slope: f32, // steepest ground a touchdown survives (radians)
+}
+
+fn default_max_landing_slope() -> f32 {
+    0.2
+}
+
+// The ground lookups search the points by x, so put hand-written profiles in order
+fn deserialize_terrain_points<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<(f32, f32)>, D::Error> {
+    let mut points = Vec::<(f32, f32)>::deserialize(deserializer)?;
+    points.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    Ok(points)
+}
+
+impl Terrain {
+    // Index of the segment under `x`, or None beyond the ends
+    fn segment(&self, x: f32) -> Option<(Vec2, Vec2)> {
+        let next = self.points.partition_point(|(px, _)| *px <= x);
+        if next == 0 || next == self.points.len() {
+            return None;
+        }
+        let (ax, ay) = self.points[next - 1];
+        let (bx, by) = self.points[next];
+        Some((Vec2::new(ax, ay), Vec2::new(bx, by)))
+    }
+
+    pub fn height_at(&self, x: f32) -> f32 {
+        match self.segment(x) {
+            Some((a, b)) => {
+                let f = (x - a.x) / (b.x - a.x).max(f32::EPSILON);
+                a.y + (b.y - a.y) * f
+            }
+            None if self.points.first().is_some_and(|(px, _)| x < *px) => self.points[0].1,
+            None => self.points.last().map_or(0.0, |(_, py)| *py),
+        }
+    }
+
+    // Ground slope under `x` (radians, positive rising to the right)
+    pub fn slope_at(&self, x: f32) -> f32 {
+        self.segment(x)
+            .map_or(0.0, |(a, b)| (b.y - a.y).atan2(b.x - a.x))
+    }
+}
+
+// A point the lander has to fly through on the way to the success box
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Waypoint {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32, // counts as reached within this distance (m)
+    #[serde(default)]
+    pub max_speed: Option<f32>, // only counts when slower than this (m/s)
+}
+
+// Standard deviations of the gaussian offsets added to the initial state at every
+// reset, drawn from the run seed so a run can be repeated
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Dispersion {
+    #[serde(default)]
+    pub x0: f32, // horizontal position (m)
+    #[serde(default)]
+    pub vx0: f32, // horizontal velocity (m/s)
+    #[serde(default)]
+    pub vy0: f32, // vertical velocity (m/s)
+    #[serde(default)]
+    pub initial_angle: f32, // rotation (radians)
+    #[serde(default)]
+    pub seed: u64, // mixed into the run seed, so levels don't share the same offsets
+}
+
+// Horizontal wind that depends only on simulation time, so runs stay reproducible
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Wind {
+    pub base: f32,           // steady force, positive pushes right (N)
+    pub gust_amplitude: f32, // peak of the periodic gust on top of the base (N)
+    pub gust_period: f32,    // time for one full gust cycle (s)
+}
+
+impl Wind {
+    // Horizontal wind force at a simulation time (N)
+    pub fn force_at(&self, time: f32) -> f32 {
+        if self.gust_period <= 0.0 {
+            return self.base;
+        }
+        self.base + self.gust_amplitude * (std::f32::consts::TAU * time / self.gust_period).sin()
+    }
+}
+
+// Exhaust plume look, so different engines and propellants can be told apart
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Visuals {
+    #[serde(default = "default_exhaust_color")]
+    pub exhaust_color: [f32; 4], // sRGB and alpha of the main engine particles
+    #[serde(default = "default_particle_intensity")]
+    pub particle_intensity: f32, // scales the number of exhaust particles (1 = normal)
+}
+
+impl Default for Visuals {
+    fn default() -> Self {
+        Self {
+            exhaust_color: default_exhaust_color(),
+            particle_intensity: default_particle_intensity(),
+        }
+    }
+}
+
+fn default_exhaust_color() -> [f32; 4] {
+    [0.8, 0.3, 0.2, 0.8]
+}
+
+fn default_particle_intensity() -> f32 {
+    1.0
+}
+
+// Uneven ground: the first touchdown gets a random kick, so the lander has to settle
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RoughTerrain {
+    pub max_bump_velocity: f32,    // largest upward velocity kick (m/s)
+    pub max_bump_angular_vel: f32, // largest angular velocity kick either way (rad/s)
+}
+
+fn default_low_fuel_fraction() -> f32 {
+    0.1
+}
+
+fn default_show_grid() -> bool {
+    true
+}
+
+impl BoundingBox {
+    // Corners (min, max) in world coordinates
+    pub fn world_bounds(&self, initial: &InitialState) -> (Vec2, Vec2) {
+        let origin = match self.reference {
+            Reference::Absolute => Vec2::ZERO,
+            Reference::Initial => Vec2::new(initial.x0, initial.y0),
+        };
+        (
+            origin + Vec2::new(self.x_min, self.y_min),
+            origin + Vec2::new(self.x_max, self.y_max),
+        )
+    }
+}
+
+impl LevelConfig {
+    // The level's terrain, unless it's a planet-center level where the ground is the
+    // planet's surface
+    pub fn flat_terrain(&self) -> Option<&Terrain> {
+        self.terrain
+            .as_ref()
+            .filter(|_| self.physics.planet_radius.is_none())
+    }
+
+    // Ground height below a horizontal position on flat-ground levels
+    pub fn ground_height(&self, x: f32) -> f32 {
+        self.flat_terrain()
+            .map_or(0.0, |terrain| terrain.height_at(x))
+    }
+
+    // Height above the ground directly below, or above the surface in planet-center mode
+    pub fn height_above_ground(&self, position: Vec2) -> f32 {
+        match self.flat_terrain() {
+            Some(terrain) => position.y - terrain.height_at(position.x),
+            None => self.physics.altitude(position),
+        }
+    }
+
+    // Ratio of maximum thrust to the fully-fuelled lander's weight. Below 1.0 the
+    // lander cannot hover.
+    pub fn initial_thrust_to_weight(&self) -> f32 {
+        let weight = -self.physics.gravity * (self.physics.dry_mass + self.initial.initial_fuel);
+        if weight <= 0.0 {
+            return f32::INFINITY;
+        }
+        self.physics.max_thrust / weight
+    }
+
+    // Point the lander is steering for once every waypoint is passed: the middle of
+    // the success box, or the target altitude above the box for ascents
+    pub fn final_target(&self) -> Vec2 {
+        let bounds = &self.success.position_box;
+        let x_center = (bounds.x_min + bounds.x_max) / 2.0;
+        match (&self.success.objective, &bounds.reference) {
+            (
+                Objective::Ascent {
+                    target_altitude, ..
+                },
+                _,
+            ) => Vec2::new(x_center, *target_altitude),
+            (Objective::Landing, Reference::Absolute) => Vec2::new(x_center, bounds.y_min),
+            (Objective::Landing, Reference::Initial) => Vec2::new(
+                self.initial.x0 + x_center,
+                self.initial.y0 + (bounds.y_min + bounds.y_max) / 2.0,
+            ),
+        }
+    }
+
+    // Unit vector from the initial position to the final target, along which down-range
+    // is measured. Sideways when the two coincide, so cross-range is then the height.
+    pub fn downrange_axis(&self) -> Vec2 {
+        let start = Vec2::new(self.initial.x0, self.initial.y0);
+        (self.final_target() - start)
+            .try_normalize()
+            .unwrap_or(Vec2::X)
+    }
+
+    // Whether any wind or turbulence pushes the lander around
+    pub fn has_wind(&self) -> bool {
+        self.wind.is_some() || self.physics.turbulence.is_some()
+    }
+
+    // Largest force the wind and turbulence can add up to along one axis (N)
+    pub fn max_wind_force(&self) -> f32 {
+        let wind = self
+            .wind
+            .as_ref()
+            .map_or(0.0, |wind| wind.base.abs() + wind.gust_amplitude.abs());
+        let turbulence = self
+            .physics
+            .turbulence
+            .as_ref()
+            .map_or(0.0, |turbulence| turbulence.amplitude.abs());
+        wind + turbulence
+    }
+
+    // Reasons the simulation can't fly the level: a gimbal range that doesn't hold the
+    // centred engine, or a mass, Isp, inertia or throttle rate that isn't positive
+    pub fn validate(&self) -> Result<(), String> {
+        let physics = &self.physics;
+        if physics.gimbal_min > 0.0 || physics.gimbal_max < 0.0 {
+            return Err(format!(
+                "the gimbal range ({} to {}) must include 0",
+                physics.gimbal_min, physics.gimbal_max
+            ));
+        }
+        for (name, value) in [
+            ("dry_mass", physics.dry_mass),
+            ("isp", physics.isp),
+            ("moment_of_inertia", physics.moment_of_inertia),
+            ("thrust_rate", physics.thrust_rate),
+        ] {
+            if value.is_nan() || value <= 0.0 {
+                return Err(format!("{} must be positive, not {}", name, value));
+            }
+        }
+        if let Some(stage) = &physics.stage {
+            if stage.dry_mass >= physics.dry_mass {
+                return Err(format!(
+                    "the stage's dry mass ({}) must be less than the lander's ({})",
+                    stage.dry_mass, physics.dry_mass
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Level numbers from here on are the user's own levels, made in the level editor
+pub const CUSTOM_LEVEL_START: usize = 1000;
+
+pub fn is_custom_level(number: usize) -> bool {
+    number >= CUSTOM_LEVEL_START
 }
 
 #[derive(Debug, Deserialize)]
@@ -78,6 +576,54 @@ pub struct LevelList {
     pub levels: Vec<String>, // List of level file names without extension
 }
 
+// Folder of loose level files (e.g. an instructor's level pack) loaded after the
+// built-in levels. Only the native build has one.
+#[cfg(not(target_arch = "wasm32"))]
+fn user_levels_dir() -> Option<std::path::PathBuf> {
+    dirs::config_dir().map(|config_dir| config_dir.join("gnc-trainer").join("levels"))
+}
+
+// Parse every .ron file in the user level folder, in file name order. A file that
+// doesn't parse or validate is logged and skipped.
+#[cfg(not(target_arch = "wasm32"))]
+fn load_user_levels() -> Vec<LevelConfig> {
+    let Some(dir) = user_levels_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new(); // No folder, no extra levels
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "ron"))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let text = std::fs::read_to_string(&path)
+                .map_err(|e| warn!("Skipping level file {}: {}", path.display(), e))
+                .ok()?;
+            let config = ron::de::from_str::<LevelConfig>(&text)
+                .map_err(|e| warn!("Skipping level file {}: {}", path.display(), e))
+                .ok()?;
+            config
+                .validate()
+                .map_err(|e| warn!("Skipping level file {}: {}", path.display(), e))
+                .ok()?;
+            info!("Loaded level '{}' from {}", config.name, path.display());
+            Some(config)
+        })
+        .collect()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn load_user_levels() -> Vec<LevelConfig> {
+    Vec::new()
+}
+
 #[derive(Default, Resource)]
 pub struct LevelManager {
     pub levels: HashMap<usize, LevelConfig>,
@@ -87,6 +633,9 @@ pub struct LevelManager {
     level_handles: Vec<Handle<RonAsset>>,
     level_list: Option<LevelList>,
     loaded_configs: Vec<(usize, LevelConfig)>, // Temporary storage for loaded configs
+    rejected_count: usize, // Built-in level files that failed validation and were skipped
+    user_configs: Vec<LevelConfig>, // Levels from the user level folder, added after the built-in ones
+    builtin_count: usize,           // Levels from level_list.ron, numbered from 0
 }
 impl LevelManager {
     pub fn new() -> Self {
@@ -97,6 +646,9 @@ impl LevelManager {
             level_handles: Vec::new(),
             level_list: None,
             loaded_configs: Vec::new(),
+            rejected_count: 0,
+            user_configs: Vec::new(),
+            builtin_count: 0,
         }
     }
 
@@ -110,14 +662,71 @@ impl LevelManager {
         self.available_levels.clear();
 
         // Insert in correct order
+        self.builtin_count = 0;
         for (idx, config) in self.loaded_configs.drain(..) {
             self.available_levels.push((idx, config.name.clone()));
             self.levels.insert(idx, config);
+            self.builtin_count = self.builtin_count.max(idx + 1);
+        }
+
+        // Then the user's level files, numbered on from the last built-in level. Levels
+        // are looked up by name, so one that reuses a name already listed is skipped.
+        // Saved code, scores and attempts are keyed by these numbers, so adding a built-in
+        // level shifts every pack level up one and leaves its saved progress behind.
+        let mut offset = 0;
+        for config in &self.user_configs {
+            if self
+                .available_levels
+                .iter()
+                .any(|(_, name)| name == &config.name)
+            {
+                warn!(
+                    "Skipping level file '{}': there is already a level with that name",
+                    config.name
+                );
+                continue;
+            }
+            let number = self.builtin_count + offset;
+            offset += 1;
+            self.available_levels.push((number, config.name.clone()));
+            self.levels.insert(number, config.clone());
         }
 
         self.loading = false;
     }
 
+    // Replace the custom levels with a new list, numbered from CUSTOM_LEVEL_START
+    pub fn set_custom_levels(&mut self, custom_levels: &[LevelConfig]) {
+        self.levels.retain(|number, _| !is_custom_level(*number));
+        self.available_levels
+            .retain(|(number, _)| !is_custom_level(*number));
+        for (index, config) in custom_levels.iter().enumerate() {
+            let number = CUSTOM_LEVEL_START + index;
+            self.available_levels.push((number, config.name.clone()));
+            self.levels.insert(number, config.clone());
+        }
+    }
+
+    // Starting script for a level. Levels that don't ship with one start from the first
+    // built-in level that uses the same control scheme.
+    pub fn default_script_path(&self, number: usize, config: &LevelConfig) -> String {
+        let template = if number < self.builtin_count {
+            number
+        } else {
+            match config.control_scheme {
+                ControlScheme::VerticalOnly => 0,
+                ControlScheme::ThrustVector => 2,
+                ControlScheme::Translate => 4,
+            }
+        };
+        format!("scripts/level{}_default.rhai", template)
+    }
+
+    // Levels from level_list.ron, which unlock one after another
+    pub fn is_builtin_level(&self, number: usize) -> bool {
+        number < self.builtin_count
+    }
+
     pub fn get_level(&self, number: usize) -> Option<LevelConfig> {
         self.levels.get(&number).cloned()
     }
@@ -160,8 +769,21 @@ pub fn load_levels(
                         }
                     } else if path_str.contains("level") && path_str.ends_with(".ron") {
                         // Process individual level file
-                        if let Some(file_name) = path_str.split('/').last() {
+                        if let Some(file_name) = path_str.split('/').next_back() {
                             if let Ok(config) = ron::de::from_str::<LevelConfig>(&asset.0) {
+                                if let Err(error) = config.validate() {
+                                    warn!("Skipping level file {}: {}", file_name, error);
+                                    level_manager.rejected_count += 1;
+                                    continue;
+                                }
+                                let twr = config.initial_thrust_to_weight();
+                                if twr < 1.0 {
+                                    warn!(
+                                        "Level '{}' has an initial thrust-to-weight ratio of {:.2} and cannot hover",
+                                        config.name, twr
+                                    );
+                                }
+
                                 // Get level index from level list
                                 if let Some(list) = &level_manager.level_list {
                                     if let Some(index) = list
@@ -185,7 +807,7 @@ pub fn load_levels(
     if level_manager.is_loading()
         && level_manager.level_list.is_some()
         && !level_manager.loaded_configs.is_empty()
-        && level_manager.loaded_configs.len()
+        && level_manager.loaded_configs.len() + level_manager.rejected_count
             == level_manager.level_list.as_ref().unwrap().levels.len()
     {
         // Finalize loading by sorting and inserting in correct order
@@ -240,5 +862,20 @@ fn setup_levels(mut commands: Commands, asset_server: Res<AssetServer>) {
     let list_handle = asset_server.load::<RonAsset>("levels/level_list.ron");
     level_manager.level_handles.push(list_handle);
 
+    level_manager.user_configs = load_user_levels();
+
     commands.insert_resource(level_manager);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terrain_points_load_in_order() {
+        let terrain: Terrain =
+            ron::from_str("(points: [(10.0, 4.0), (-10.0, 0.0), (0.0, 2.0)])").unwrap();
+        assert_eq!(terrain.points, vec![(-10.0, 0.0), (0.0, 2.0), (10.0, 4.0)]);
+        assert!((terrain.height_at(5.0) - 3.0).abs() < 1e-5);
+    }
+}
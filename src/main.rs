@@ -2,11 +2,22 @@ use bevy::{asset::AssetMetaCheck, log::LogPlugin, prelude::*};
 use bevy_egui::EguiPlugin;
 
 mod assets;
+mod audio;
+mod challenge;
 mod constants;
+#[cfg(not(target_arch = "wasm32"))]
+mod grading;
+mod headless;
+mod level_editor;
 mod levels;
+mod manual;
+mod monte_carlo;
 mod persistence;
 mod rhai_api;
+#[cfg(target_arch = "wasm32")]
+mod share;
 mod simulation;
+mod snippets;
 mod ui;
 mod visualization;
 
@@ -14,14 +25,21 @@ use bevy_persistent::Persistent;
 use levels::{CurrentLevel, GameLoadState, LevelManager, LevelPlugin};
 use persistence::{setup_persistence, LevelProgress};
 use rhai_api::ScriptEngine;
-use simulation::{reset_simulation, simulation_system, LanderState};
+use simulation::{
+    reset_simulation, score_run, simulation_system, trajectory_playback_system, LanderState,
+    SimStats, TelemetryLog, TouchdownReport, Trajectory, TrajectoryPlayback,
+    SCORE_TRAJECTORY_POINTS,
+};
 use ui::{
-    about_popup, handle_escape, handle_script_loading, hint_popup, level_complete_popup,
-    level_select_ui, ui_system, AboutPopupState, EditorState, GameState, HintPopupState,
-    LevelCompletePopup, SimulationState,
+    about_popup, auto_pause_offscreen, challenge_popup, control_hints_overlay, countdown_overlay,
+    handle_escape, handle_script_loading, hint_popup, hud_overlay, level_complete_popup,
+    level_select_ui, monte_carlo_popup, sim_stats_overlay, stats_popup, success_checklist_overlay,
+    telemetry_plot_window, trajectory_review_ui, ui_system, AboutPopupState, ChallengePopupState,
+    EditorState, GameState, HintPopupState, LevelCompletePopup, SimulationState, StatsPopupState,
 };
 use visualization::{
-    spawn_visualization, CameraState, MainCamera, ResetVisibilityFlag, VisualizationPlugin,
+    spawn_visualization, CameraState, HeatmapDirty, MainCamera, ResetVisibilityFlag,
+    VisualizationPlugin,
 };
 
 #[cfg(target_arch = "wasm32")]
@@ -52,71 +70,122 @@ fn is_mobile() -> bool {
 }
 
 fn main() {
-    App::new()
-        .add_plugins(
-            DefaultPlugins
-                .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        title: "GNC Trainer".into(),
-                        resolution: (1280., 720.).into(),
-                        // Enable default event handling on mobile for virtual keyboard
-                        // Disable on desktop for copy-paste shortcuts
-                        prevent_default_event_handling: is_mobile(),
-                        fit_canvas_to_parent: true,
-                        ..default()
-                    }),
-                    ..default()
-                })
-                .set(LogPlugin {
-                    level: bevy::log::Level::DEBUG,
-                    filter: "info,wgpu_core=warn,wgpu_hal=warn,bevy_persistent::persistent=warn"
-                        .into(),
-                    ..default()
-                })
-                .set(AssetPlugin {
-                    meta_check: AssetMetaCheck::Never,
+    // Headless grading mode for automated course grading
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(exit_code) = grading::grade_from_args() {
+        std::process::exit(exit_code);
+    }
+
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "GNC Trainer".into(),
+                    resolution: (1280., 720.).into(),
+                    // Enable default event handling on mobile for virtual keyboard
+                    // Disable on desktop for copy-paste shortcuts
+                    prevent_default_event_handling: is_mobile(),
+                    fit_canvas_to_parent: true,
                     ..default()
                 }),
-        )
-        .add_plugins(EguiPlugin)
-        .add_plugins(LevelPlugin)
-        .insert_resource(EditorState::default())
-        .insert_resource(LanderState::default())
-        .insert_resource(ScriptEngine::default())
-        .insert_resource(visualization::CameraState::default())
-        .insert_resource(ResetVisibilityFlag::default())
-        .insert_resource(visualization::ResetVisualization::default())
-        .insert_resource(AboutPopupState::default())
-        .insert_resource(HintPopupState::default())
-        .init_state::<GameState>()
-        .insert_resource(State::new(GameState::LevelSelect))
-        .insert_resource(LevelCompletePopup::default())
-        .init_asset::<assets::ScriptAsset>()
-        .init_asset_loader::<assets::ScriptAssetLoader>()
-        .add_systems(
-            OnEnter(GameLoadState::Ready),
-            (setup, setup_persistence, spawn_visualization),
-        )
-        .add_plugins(VisualizationPlugin)
-        .add_systems(
-            Update,
+                ..default()
+            })
+            .set(LogPlugin {
+                level: bevy::log::Level::DEBUG,
+                filter: "info,wgpu_core=warn,wgpu_hal=warn,bevy_persistent::persistent=warn".into(),
+                ..default()
+            })
+            .set(AssetPlugin {
+                meta_check: AssetMetaCheck::Never,
+                ..default()
+            }),
+    )
+    .add_plugins(EguiPlugin)
+    .add_plugins(LevelPlugin)
+    .add_plugins(audio::GameAudioPlugin)
+    .insert_resource(EditorState::default())
+    .insert_resource(LanderState::default())
+    .insert_resource(Trajectory::default())
+    .insert_resource(TelemetryLog::default())
+    .insert_resource(TouchdownReport::default())
+    .insert_resource(manual::ManualInput::default())
+    .insert_resource(TrajectoryPlayback::default())
+    .insert_resource(monte_carlo::MonteCarlo::default())
+    .insert_resource(ScriptEngine::default())
+    .insert_resource(visualization::CameraState::default())
+    .insert_resource(ResetVisibilityFlag::default())
+    .insert_resource(visualization::ResetVisualization::default())
+    .insert_resource(visualization::ViewOptions::default())
+    .insert_resource(SimStats::default())
+    .insert_resource(visualization::HeatmapDirty::default())
+    .insert_resource(AboutPopupState::default())
+    .insert_resource(HintPopupState::default())
+    .insert_resource(ChallengePopupState::default())
+    .insert_resource(StatsPopupState::default())
+    .init_state::<GameState>()
+    .insert_resource(State::new(GameState::LevelSelect))
+    .insert_resource(LevelCompletePopup::default())
+    .init_resource::<level_editor::LevelEditorState>()
+    .init_asset::<assets::ScriptAsset>()
+    .init_asset_loader::<assets::ScriptAssetLoader>()
+    .add_systems(
+        OnEnter(GameLoadState::Ready),
+        (
+            setup,
+            setup_persistence,
+            level_editor::setup_custom_levels,
+            spawn_visualization,
+        ),
+    )
+    .add_systems(
+        OnEnter(GameState::LevelEditor),
+        level_editor::enter_level_editor,
+    )
+    .add_plugins(VisualizationPlugin)
+    .add_systems(
+        Update,
+        (
+            (level_select_ui, stats_popup).run_if(in_state(GameState::LevelSelect)),
+            level_editor::level_editor_ui.run_if(in_state(GameState::LevelEditor)),
+            level_complete_popup,
+            about_popup,
             (
-                level_select_ui.run_if(in_state(GameState::LevelSelect)),
-                level_complete_popup,
-                about_popup,
+                (ui_system, countdown_overlay).chain(),
+                control_hints_overlay,
+                sim_stats_overlay,
+                hud_overlay,
+                success_checklist_overlay,
+                telemetry_plot_window,
+                (monte_carlo::run_monte_carlo_trials, monte_carlo_popup).chain(),
                 (
-                    ui_system,
+                    manual::read_manual_input,
                     simulation_system.run_if(run_simulation),
-                    (level_completion_check, save_current_editor_state).chain(),
-                    handle_escape,
-                    handle_script_loading,
-                    hint_popup,
+                    auto_pause_offscreen,
                 )
-                    .run_if(in_state(GameState::Playing)),
+                    .chain(),
+                (level_completion_check, save_current_editor_state).chain(),
+                record_attempt_history,
+                handle_escape,
+                handle_script_loading,
+                hint_popup,
+                challenge_popup,
+                (trajectory_review_ui, trajectory_playback_system).chain(),
             )
-                .run_if(in_state(GameLoadState::Ready)),
+                .run_if(in_state(GameState::Playing)),
         )
-        .run();
+            .run_if(in_state(GameLoadState::Ready)),
+    );
+
+    // Web links can open a level with code already in the editor
+    #[cfg(target_arch = "wasm32")]
+    app.add_systems(
+        Update,
+        ui::open_share_link
+            .run_if(in_state(GameLoadState::Ready).and(in_state(GameState::LevelSelect))),
+    );
+
+    app.run();
 }
 
 fn setup(
@@ -124,13 +193,21 @@ fn setup(
     mut lander_state: ResMut<LanderState>,
     current_level: Res<CurrentLevel>,
     mut camera_state: ResMut<CameraState>,
+    mut trajectory: ResMut<Trajectory>,
+    mut script_engine: ResMut<ScriptEngine>,
 ) {
     commands.spawn((Camera2d, MainCamera));
-    reset_simulation(&mut lander_state, &current_level, &mut camera_state);
+    reset_simulation(
+        &mut lander_state,
+        &current_level,
+        &mut camera_state,
+        &mut trajectory,
+        &mut script_engine,
+    );
 }
 
 fn run_simulation(state: Res<EditorState>, lander_state: Res<LanderState>) -> bool {
-    state.simulation_state == SimulationState::Running
+    (state.simulation_state == SimulationState::Running || state.step_requested)
         && !lander_state.landed
         && !lander_state.crashed
 }
@@ -139,32 +216,83 @@ pub fn save_current_editor_state(
     editor_state: Res<EditorState>,
     current_level: Res<CurrentLevel>,
     level_manager: Res<LevelManager>,
-    progress: ResMut<Persistent<LevelProgress>>,
+    mut progress: ResMut<Persistent<LevelProgress>>,
+) {
+    if let Some((level_num, _)) = level_manager
+        .available_levels
+        .iter()
+        .find(|(_, name)| name == &current_level.config.name)
+    {
+        let _ = persistence::save_editor_state(
+            *level_num,
+            editor_state.code.clone(),
+            editor_state.notes.clone(),
+            &mut progress,
+        );
+    }
+}
+
+// Add each finished run to the level's attempt history (when the player opted in)
+#[allow(clippy::too_many_arguments)]
+fn record_attempt_history(
+    lander_state: Res<LanderState>,
+    mut trajectory: ResMut<Trajectory>,
+    playback: Res<TrajectoryPlayback>,
+    progress: Res<Persistent<persistence::LevelProgress>>,
+    mut history: ResMut<Persistent<persistence::AttemptHistory>>,
+    current_level: Res<CurrentLevel>,
+    level_manager: Res<LevelManager>,
+    mut heatmap_dirty: ResMut<HeatmapDirty>,
 ) {
+    let finished = lander_state.landed || lander_state.crashed;
+    if !finished || playback.active || trajectory.attempt_saved {
+        return;
+    }
+    trajectory.attempt_saved = true;
+
+    if !progress.record_attempts {
+        return;
+    }
     if let Some((level_num, _)) = level_manager
         .available_levels
         .iter()
         .find(|(_, name)| name == &current_level.config.name)
     {
-        let _ = persistence::save_editor_state(*level_num, editor_state.code.clone(), progress);
+        let mut path = trajectory.downsampled_path(SCORE_TRAJECTORY_POINTS);
+        path.push((lander_state.position.x, lander_state.position.y));
+        let attempt = persistence::Attempt {
+            crashed: lander_state.crashed,
+            trajectory: path,
+        };
+        let _ = persistence::record_attempt(*level_num, attempt, &progress, &mut history);
+        heatmap_dirty.0 = true;
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn level_completion_check(
     editor_state: Res<EditorState>,
     lander_state: Res<LanderState>,
-    progress: ResMut<Persistent<persistence::LevelProgress>>,
+    mut progress: ResMut<Persistent<persistence::LevelProgress>>,
     current_level: Res<CurrentLevel>,
     level_manager: Res<LevelManager>,
     mut popup: ResMut<LevelCompletePopup>,
+    trajectory: Res<Trajectory>,
+    mut touchdown: ResMut<TouchdownReport>,
 ) {
     if lander_state.landed && editor_state.simulation_state == SimulationState::Running {
+        *touchdown = TouchdownReport::capture(&lander_state, &current_level.config);
         if let Some((level_num, _)) = level_manager
             .available_levels
             .iter()
             .find(|(_, name)| name == &current_level.config.name)
         {
-            let _ = persistence::mark_level_complete(*level_num, progress);
+            let _ = persistence::mark_level_complete(*level_num, &mut progress);
+            // Runs slowed down by the precision assist don't count towards best runs
+            if !lander_state.assisted {
+                let score = score_run(&lander_state, &current_level.config, &trajectory);
+                let _ = persistence::record_score(*level_num, score, &mut progress);
+            }
             popup.show = true;
             popup.completed_level = *level_num;
         }
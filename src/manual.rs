@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+
+use crate::levels::{ControlScheme, LevelConfig};
+use crate::rhai_api::{ControlOutput, SimpleControl, TranslateControl, VectoredControl};
+use crate::simulation::LanderState;
+use crate::ui::EditorState;
+
+pub const TOGGLE_HINTS_KEY: KeyCode = KeyCode::F1;
+
+// Throttle change per second while a throttle key is held
+const THROTTLE_RAMP_RATE: f32 = 0.5;
+// Stick deflection below which the gamepad is treated as centered
+const GAMEPAD_DEADZONE: f32 = 0.15;
+
+// Who flies the lander: the script's control() or the player at the keyboard
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum ControlSource {
+    #[default]
+    Script,
+    Manual,
+}
+
+// Latest manual commands, read from the keyboard and gamepad every frame
+#[derive(Resource, Default)]
+pub struct ManualInput {
+    pub throttle: f32, // 0.0 to 1.0, ramps while the keys are held
+    pub steer: f32,    // -1.0 (left) to 1.0 (right), springs back to 0 when released
+}
+
+impl ManualInput {
+    // The commands in the same form a script returns them, so the simulation applies
+    // the usual rate limits and clamps
+    pub fn control_output(&self, config: &LevelConfig, attitude_assist: bool) -> ControlOutput {
+        match config.control_scheme {
+            ControlScheme::VerticalOnly => ControlOutput::Simple(SimpleControl {
+                thrust: self.throttle,
+            }),
+            // With the assist on, the second output is a sideways command
+            ControlScheme::ThrustVector if attitude_assist => {
+                ControlOutput::Vectored(VectoredControl {
+                    thrust: self.throttle,
+                    gimbal: self.steer,
+                })
+            }
+            // Steering left deflects the thrust to the left, using the full gimbal travel
+            // either way
+            ControlScheme::ThrustVector => ControlOutput::Vectored(VectoredControl {
+                thrust: self.throttle,
+                gimbal: if self.steer > 0.0 {
+                    self.steer * config.physics.gimbal_min
+                } else {
+                    -self.steer * config.physics.gimbal_max
+                },
+            }),
+            ControlScheme::Translate => ControlOutput::Translate(TranslateControl {
+                thrust: self.throttle,
+                lateral: self.steer,
+            }),
+        }
+    }
+}
+
+// (keys, action) pairs describing the manual controls for a control scheme
+pub fn keybinding_hints(scheme: &ControlScheme) -> Vec<(&'static str, &'static str)> {
+    let mut hints = vec![
+        ("Space", "Increase throttle"),
+        ("Shift", "Decrease throttle"),
+    ];
+    match scheme {
+        ControlScheme::VerticalOnly => {}
+        ControlScheme::ThrustVector => {
+            hints.push(("Left / A", "Gimbal left"));
+            hints.push(("Right / D", "Gimbal right"));
+        }
+        ControlScheme::Translate => {
+            hints.push(("Left / A", "Lateral thrust left"));
+            hints.push(("Right / D", "Lateral thrust right"));
+        }
+    }
+    hints.push(("F1", "Show / hide this overlay"));
+    hints
+}
+
+// Read the keyboard and the first gamepad into the manual commands. Keys are ignored
+// while the code editor or another text field has focus.
+#[allow(clippy::too_many_arguments)]
+pub fn read_manual_input(
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut contexts: EguiContexts,
+    editor_state: Res<EditorState>,
+    lander_state: Res<LanderState>,
+    mut input: ResMut<ManualInput>,
+    mut previous_source: Local<ControlSource>,
+) {
+    // Pick up the throttle where the script left it, so switching mid-run doesn't jump
+    if editor_state.control_source == ControlSource::Manual
+        && *previous_source != ControlSource::Manual
+    {
+        input.throttle = lander_state.thrust_level;
+        input.steer = 0.0;
+    }
+    *previous_source = editor_state.control_source;
+    if editor_state.control_source != ControlSource::Manual {
+        return;
+    }
+
+    let typing = contexts.ctx_mut().wants_keyboard_input();
+    let held = |codes: &[KeyCode]| !typing && keys.any_pressed(codes.iter().copied());
+    let gamepad = gamepads.iter().next();
+
+    let mut throttle_direction = 0.0;
+    if held(&[KeyCode::Space])
+        || gamepad.is_some_and(|pad| pad.pressed(GamepadButton::RightTrigger2))
+    {
+        throttle_direction += 1.0;
+    }
+    if held(&[KeyCode::ShiftLeft, KeyCode::ShiftRight])
+        || gamepad.is_some_and(|pad| pad.pressed(GamepadButton::LeftTrigger2))
+    {
+        throttle_direction -= 1.0;
+    }
+    input.throttle = (input.throttle + throttle_direction * THROTTLE_RAMP_RATE * time.delta_secs())
+        .clamp(0.0, 1.0);
+
+    let mut steer = 0.0;
+    if held(&[KeyCode::ArrowLeft, KeyCode::KeyA]) {
+        steer -= 1.0;
+    }
+    if held(&[KeyCode::ArrowRight, KeyCode::KeyD]) {
+        steer += 1.0;
+    }
+    if let Some(stick) = gamepad.map(|pad| pad.left_stick().x) {
+        if stick.abs() > GAMEPAD_DEADZONE {
+            steer = stick;
+        }
+    }
+    input.steer = steer;
+}
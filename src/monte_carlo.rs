@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+
+use crate::headless::{RunOutcome, Trial, TrialResult};
+use crate::levels::LevelConfig;
+use crate::rhai_api::ScriptEngine;
+
+// Trials in a batch, seeded 0..TRIAL_COUNT so batches of different scripts compare
+pub const TRIAL_COUNT: u64 = 100;
+// Simulation steps flown per frame; a batch, and a long trial within it, is spread
+// over frames to keep the UI responsive
+const STEPS_PER_FRAME: usize = 2000;
+
+// Batch of headless runs of the editor script over the level's dispersion. The batch
+// has its own script engine and level copy, so it doesn't disturb the live run.
+#[derive(Resource, Default)]
+pub struct MonteCarlo {
+    pub show: bool,
+    engine: Option<ScriptEngine>,
+    config: Option<LevelConfig>,
+    trial: Option<Trial>, // Trial in flight, carried over to the next frame
+    pub results: Vec<TrialResult>,
+    pub error: Option<String>, // Why the batch couldn't start
+}
+
+// Counts and touchdown-speed statistics over the finished trials
+pub struct BatchSummary {
+    pub landed: usize,
+    pub crashed: usize,
+    pub timed_out: usize,
+    pub script_errors: usize,
+    pub touchdown_speeds: Vec<f32>, // m/s, for the trials that reached the ground
+    pub landed_fuel: f32,           // kg, summed over the landed trials
+}
+
+impl BatchSummary {
+    pub fn success_rate(&self, total: usize) -> f32 {
+        if total == 0 {
+            return 0.0;
+        }
+        self.landed as f32 / total as f32
+    }
+
+    // (mean, min, max) touchdown speed
+    pub fn touchdown_stats(&self) -> Option<(f32, f32, f32)> {
+        if self.touchdown_speeds.is_empty() {
+            return None;
+        }
+        let mean = self.touchdown_speeds.iter().sum::<f32>() / self.touchdown_speeds.len() as f32;
+        let min = self
+            .touchdown_speeds
+            .iter()
+            .copied()
+            .fold(f32::INFINITY, f32::min);
+        let max = self
+            .touchdown_speeds
+            .iter()
+            .copied()
+            .fold(f32::NEG_INFINITY, f32::max);
+        Some((mean, min, max))
+    }
+
+    pub fn mean_landed_fuel(&self) -> Option<f32> {
+        (self.landed > 0).then(|| self.landed_fuel / self.landed as f32)
+    }
+}
+
+impl MonteCarlo {
+    // Compile the script into a fresh engine and queue the trials
+    pub fn start(&mut self, code: &str, config: &LevelConfig) {
+        self.show = true;
+        self.results.clear();
+        self.error = None;
+        self.trial = None;
+
+        let mut engine = ScriptEngine::default();
+        engine.set_level(config);
+        if let Err(error) = engine.compile_script(code) {
+            self.error = Some(error);
+            self.engine = None;
+            return;
+        }
+        engine.activate_script();
+        self.engine = Some(engine);
+        self.config = Some(config.clone());
+    }
+
+    pub fn running(&self) -> bool {
+        self.engine.is_some()
+    }
+
+    pub fn summary(&self) -> BatchSummary {
+        let mut summary = BatchSummary {
+            landed: 0,
+            crashed: 0,
+            timed_out: 0,
+            script_errors: 0,
+            touchdown_speeds: Vec::new(),
+            landed_fuel: 0.0,
+        };
+        for result in &self.results {
+            match result.outcome {
+                RunOutcome::Landed => {
+                    summary.landed += 1;
+                    summary.landed_fuel += result.fuel_remaining;
+                }
+                RunOutcome::Crashed => summary.crashed += 1,
+                RunOutcome::TimedOut => summary.timed_out += 1,
+                RunOutcome::ScriptError(_) => summary.script_errors += 1,
+            }
+            summary.touchdown_speeds.extend(result.touchdown_speed);
+        }
+        summary
+    }
+
+    // First script error hit by any trial, to show in the summary
+    pub fn first_script_error(&self) -> Option<(u64, &str)> {
+        self.results
+            .iter()
+            .find_map(|result| match &result.outcome {
+                RunOutcome::ScriptError(error) => Some((result.seed, error.as_str())),
+                _ => None,
+            })
+    }
+}
+
+// Fly the next trials of a running batch
+pub fn run_monte_carlo_trials(mut monte_carlo: ResMut<MonteCarlo>) {
+    let MonteCarlo {
+        engine,
+        config,
+        trial,
+        results,
+        ..
+    } = &mut *monte_carlo;
+    let (Some(script_engine), Some(config)) = (engine.as_mut(), config.as_ref()) else {
+        return;
+    };
+
+    let mut budget = STEPS_PER_FRAME;
+    while budget > 0 && (results.len() as u64) < TRIAL_COUNT {
+        let seed = results.len() as u64;
+        let flying = trial.get_or_insert_with(|| Trial::start(config, script_engine, seed));
+        match flying.advance(config, script_engine, &mut budget) {
+            Some(result) => {
+                results.push(result);
+                *trial = None;
+            }
+            None => break,
+        }
+    }
+
+    if results.len() as u64 >= TRIAL_COUNT {
+        *engine = None;
+    }
+}
@@ -1,36 +1,277 @@
 use bevy::prelude::*;
 use bevy_persistent::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::levels::LevelManager;
+
 #[derive(Resource, Serialize, Deserialize, Clone, Default)]
 pub struct LevelProgress {
     pub completed_levels: Vec<usize>,
     pub max_level_reached: usize,
-    pub editor_states: HashMap<usize, String>,
+    #[serde(deserialize_with = "deserialize_editor_states")]
+    pub editor_states: HashMap<usize, Vec<NamedScript>>, // Solution slots per level
+    #[serde(default)]
+    pub active_slots: HashMap<usize, usize>, // Slot each level's editor is working on
+    #[serde(default)]
+    pub best_scores: HashMap<usize, LevelScore>,
+    #[serde(default)]
+    pub notes: HashMap<usize, String>,
+    #[serde(default)]
+    pub record_attempts: bool, // Opt-in: keep every attempt's path for the heatmap
+    // Attempts used to be saved here; they are moved to AttemptHistory on load
+    #[serde(default, skip_serializing)]
+    pub attempts: HashMap<usize, Vec<Attempt>>,
+    #[serde(default)]
+    pub units: UnitSystem,
+    #[serde(default)]
+    pub angle_units: AngleUnit,
 }
 
-pub fn setup_persistence(mut commands: Commands) {
-    let config_dir = dirs::config_dir()
+// One saved solution for a level
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NamedScript {
+    pub name: String,
+    pub code: String,
+}
+
+// Name of the slot a level's code is saved to before any others are created
+pub const DEFAULT_SLOT_NAME: &str = "default";
+
+// Older saves kept a single script per level; load those as one slot named "default"
+fn deserialize_editor_states<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<HashMap<usize, Vec<NamedScript>>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum SavedScripts {
+        Single(String),
+        Slots(Vec<NamedScript>),
+    }
+
+    let saved = HashMap::<usize, SavedScripts>::deserialize(deserializer)?;
+    Ok(saved
+        .into_iter()
+        .map(|(level, saved)| {
+            let slots = match saved {
+                SavedScripts::Single(code) => vec![NamedScript {
+                    name: DEFAULT_SLOT_NAME.into(),
+                    code,
+                }],
+                SavedScripts::Slots(slots) => slots,
+            };
+            (level, slots)
+        })
+        .collect())
+}
+
+// Units the telemetry and HUD show; the simulation and the script API are always SI
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+const FEET_PER_METER: f32 = 3.28084;
+const POUNDS_PER_KILOGRAM: f32 = 2.20462;
+const POUNDS_FORCE_PER_NEWTON: f32 = 0.224809;
+
+impl UnitSystem {
+    // Lengths, and speeds per second
+    pub fn length(self, meters: f32) -> f32 {
+        match self {
+            UnitSystem::Metric => meters,
+            UnitSystem::Imperial => meters * FEET_PER_METER,
+        }
+    }
+
+    pub fn length_unit(self) -> &'static str {
+        match self {
+            UnitSystem::Metric => "m",
+            UnitSystem::Imperial => "ft",
+        }
+    }
+
+    pub fn speed_unit(self) -> &'static str {
+        match self {
+            UnitSystem::Metric => "m/s",
+            UnitSystem::Imperial => "ft/s",
+        }
+    }
+
+    pub fn mass(self, kilograms: f32) -> f32 {
+        match self {
+            UnitSystem::Metric => kilograms,
+            UnitSystem::Imperial => kilograms * POUNDS_PER_KILOGRAM,
+        }
+    }
+
+    pub fn mass_unit(self) -> &'static str {
+        match self {
+            UnitSystem::Metric => "kg",
+            UnitSystem::Imperial => "lb",
+        }
+    }
+
+    pub fn force(self, newtons: f32) -> f32 {
+        match self {
+            UnitSystem::Metric => newtons,
+            UnitSystem::Imperial => newtons * POUNDS_FORCE_PER_NEWTON,
+        }
+    }
+
+    pub fn force_unit(self) -> &'static str {
+        match self {
+            UnitSystem::Metric => "N",
+            UnitSystem::Imperial => "lbf",
+        }
+    }
+}
+
+// Angle display, chosen separately from the unit system
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum AngleUnit {
+    #[default]
+    Degrees,
+    Radians,
+}
+
+impl AngleUnit {
+    pub fn angle(self, radians: f32) -> f32 {
+        match self {
+            AngleUnit::Degrees => radians.to_degrees(),
+            AngleUnit::Radians => radians,
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            AngleUnit::Degrees => "°",
+            AngleUnit::Radians => " rad",
+        }
+    }
+}
+
+// Paths of finished runs for the heatmap. Kept apart from LevelProgress so that
+// recording a run doesn't rewrite the scripts, scores and ghosts as well.
+#[derive(Resource, Serialize, Deserialize, Clone, Default)]
+pub struct AttemptHistory {
+    pub attempts: HashMap<usize, Vec<Attempt>>,
+}
+
+// Maximum number of attempts kept per level; the oldest are dropped first
+const MAX_ATTEMPTS_PER_LEVEL: usize = 50;
+
+// Path of a finished run, successful or not, for the attempt heatmap
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Attempt {
+    pub crashed: bool,
+    pub trajectory: Vec<(f32, f32)>, // downsampled (x, y) path; the last point is where it ended
+}
+
+// Summary of a successful run, used to keep each level's best attempt
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct LevelScore {
+    pub fuel_remaining: f32,         // kg
+    pub time: f32,                   // seconds from start to completion
+    pub touchdown_speed: f32,        // m/s
+    pub accuracy: f32,               // distance from the target at completion (m)
+    pub trajectory: Vec<(f32, f32)>, // downsampled (x, y) path for thumbnails
+    #[serde(default)]
+    pub ghost: Vec<GhostSample>, // timed states for replaying the run as a ghost
+}
+
+// Lander state at one moment of a saved run, enough to draw it again
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+pub struct GhostSample {
+    pub t: f32,
+    pub x: f32,
+    pub y: f32,
+    pub rotation: f32,
+    pub thrust: f32,
+}
+
+impl LevelScore {
+    // More fuel left wins, with the faster run breaking ties
+    pub fn is_better_than(&self, other: &LevelScore) -> bool {
+        if self.fuel_remaining != other.fuel_remaining {
+            self.fuel_remaining > other.fuel_remaining
+        } else {
+            self.time < other.time
+        }
+    }
+}
+
+// Where the progress is saved. In the browser bevy_persistent maps paths under
+// "local" to a window.localStorage key.
+#[cfg(target_arch = "wasm32")]
+fn progress_path() -> PathBuf {
+    PathBuf::from("local").join("gnc-trainer-progress")
+}
+
+#[cfg(target_arch = "wasm32")]
+fn attempts_path() -> PathBuf {
+    PathBuf::from("local").join("gnc-trainer-attempts")
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn config_dir() -> PathBuf {
+    dirs::config_dir()
         .map(|native_config_dir| native_config_dir.join("lander-game"))
-        .unwrap_or(PathBuf::from("local/configuration"));
+        .unwrap_or(PathBuf::from("local/configuration"))
+}
 
-    commands.insert_resource(
-        Persistent::<LevelProgress>::builder()
-            .name("level_progress")
-            .format(StorageFormat::Json)
-            .path(config_dir.join("progress.json"))
-            .default(LevelProgress::default())
-            .revertible(true) // Allow reverting to default if file is corrupted
-            .build()
-            .expect("Failed to initialize level progress"),
-    );
+#[cfg(not(target_arch = "wasm32"))]
+fn progress_path() -> PathBuf {
+    config_dir().join("progress.json")
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn attempts_path() -> PathBuf {
+    config_dir().join("attempts.json")
+}
+
+pub fn setup_persistence(mut commands: Commands) {
+    let mut progress = Persistent::<LevelProgress>::builder()
+        .name("level_progress")
+        .format(StorageFormat::Json)
+        .path(progress_path())
+        .default(LevelProgress::default())
+        .revertible(true) // Allow reverting to default if file is corrupted
+        .build()
+        .expect("Failed to initialize level progress");
+    let mut history = Persistent::<AttemptHistory>::builder()
+        .name("attempt_history")
+        .format(StorageFormat::Json)
+        .path(attempts_path())
+        .default(AttemptHistory::default())
+        .revertible(true)
+        .build()
+        .expect("Failed to initialize attempt history");
+
+    // Move attempts from older progress files into their own store
+    if !progress.attempts.is_empty() {
+        let legacy = std::mem::take(&mut progress.attempts);
+        let _ = history.update(|history| {
+            for (level, attempts) in &legacy {
+                history
+                    .attempts
+                    .entry(*level)
+                    .or_insert_with(|| attempts.clone());
+            }
+        });
+        let _ = progress.persist();
+    }
+
+    commands.insert_resource(progress);
+    commands.insert_resource(history);
 }
 
 pub fn mark_level_complete(
     level: usize,
-    mut progress: ResMut<Persistent<LevelProgress>>,
+    progress: &mut Persistent<LevelProgress>,
 ) -> Result<(), String> {
     progress
         .update(|progress| {
@@ -43,24 +284,234 @@ pub fn mark_level_complete(
         .map_err(|e| e.to_string())
 }
 
+// Called every frame, so only touch the file when the code or notes have changed
 pub fn save_editor_state(
     level: usize,
     code: String,
-    mut progress: ResMut<Persistent<LevelProgress>>,
+    notes: String,
+    progress: &mut Persistent<LevelProgress>,
+) -> Result<(), String> {
+    let unchanged = get_editor_state(level, progress).is_some_and(|saved| saved == code)
+        && progress.notes.get(&level).map_or("", String::as_str) == notes;
+    if unchanged {
+        return Ok(());
+    }
+
+    progress
+        .update(|progress| {
+            let slots = progress.editor_states.entry(level).or_default();
+            let active = progress.active_slots.get(&level).copied().unwrap_or(0);
+            match slots.get_mut(active) {
+                Some(slot) => slot.code = code.clone(),
+                None => {
+                    slots.push(NamedScript {
+                        name: DEFAULT_SLOT_NAME.into(),
+                        code: code.clone(),
+                    });
+                    progress.active_slots.insert(level, slots.len() - 1);
+                }
+            }
+            if notes.is_empty() {
+                progress.notes.remove(&level);
+            } else {
+                progress.notes.insert(level, notes.clone());
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+// Add a solution slot and make it the one the editor works on
+pub fn add_script_slot(
+    level: usize,
+    name: String,
+    code: String,
+    progress: &mut Persistent<LevelProgress>,
+) -> Result<(), String> {
+    progress
+        .update(|progress| {
+            let slots = progress.editor_states.entry(level).or_default();
+            slots.push(NamedScript {
+                name: name.clone(),
+                code: code.clone(),
+            });
+            progress.active_slots.insert(level, slots.len() - 1);
+        })
+        .map_err(|e| e.to_string())
+}
+
+pub fn select_script_slot(
+    level: usize,
+    index: usize,
+    progress: &mut Persistent<LevelProgress>,
+) -> Result<(), String> {
+    progress
+        .update(|progress| {
+            progress.active_slots.insert(level, index);
+        })
+        .map_err(|e| e.to_string())
+}
+
+pub fn rename_script_slot(
+    level: usize,
+    index: usize,
+    name: String,
+    progress: &mut Persistent<LevelProgress>,
+) -> Result<(), String> {
+    progress
+        .update(|progress| {
+            if let Some(slot) = progress
+                .editor_states
+                .get_mut(&level)
+                .and_then(|slots| slots.get_mut(index))
+            {
+                slot.name = name.clone();
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+// Remove a slot; the editor moves to the slot before it
+pub fn delete_script_slot(
+    level: usize,
+    index: usize,
+    progress: &mut Persistent<LevelProgress>,
+) -> Result<(), String> {
+    progress
+        .update(|progress| {
+            let Some(slots) = progress.editor_states.get_mut(&level) else {
+                return;
+            };
+            if index < slots.len() {
+                slots.remove(index);
+            }
+            let active = progress.active_slots.get(&level).copied().unwrap_or(0);
+            if active >= index && active > 0 {
+                progress.active_slots.insert(level, active - 1);
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+// Store the score if it beats the level's previous best
+pub fn record_score(
+    level: usize,
+    score: LevelScore,
+    progress: &mut Persistent<LevelProgress>,
+) -> Result<(), String> {
+    let is_best = progress
+        .best_scores
+        .get(&level)
+        .is_none_or(|best| score.is_better_than(best));
+    if !is_best {
+        return Ok(());
+    }
+
+    progress
+        .update(|progress| {
+            progress.best_scores.insert(level, score.clone());
+        })
+        .map_err(|e| e.to_string())
+}
+
+// Add a finished run to the level's attempt history if recording is enabled
+pub fn record_attempt(
+    level: usize,
+    attempt: Attempt,
+    progress: &Persistent<LevelProgress>,
+    history: &mut Persistent<AttemptHistory>,
+) -> Result<(), String> {
+    if !progress.record_attempts {
+        return Ok(());
+    }
+
+    history
+        .update(|history| {
+            let attempts = history.attempts.entry(level).or_default();
+            attempts.push(attempt.clone());
+            if attempts.len() > MAX_ATTEMPTS_PER_LEVEL {
+                let excess = attempts.len() - MAX_ATTEMPTS_PER_LEVEL;
+                attempts.drain(..excess);
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+pub fn set_record_attempts(
+    enabled: bool,
+    progress: &mut Persistent<LevelProgress>,
+) -> Result<(), String> {
+    progress
+        .update(|progress| {
+            progress.record_attempts = enabled;
+        })
+        .map_err(|e| e.to_string())
+}
+
+pub fn set_display_units(
+    units: UnitSystem,
+    angle_units: AngleUnit,
+    progress: &mut Persistent<LevelProgress>,
 ) -> Result<(), String> {
     progress
         .update(|progress| {
-            progress.editor_states.insert(level, code.clone());
+            progress.units = units;
+            progress.angle_units = angle_units;
+        })
+        .map_err(|e| e.to_string())
+}
+
+pub fn clear_attempts(history: &mut Persistent<AttemptHistory>) -> Result<(), String> {
+    history
+        .update(|history| {
+            history.attempts.clear();
         })
         .map_err(|e| e.to_string())
 }
 
+pub fn get_attempts(level: usize, history: &Persistent<AttemptHistory>) -> &[Attempt] {
+    history
+        .attempts
+        .get(&level)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+pub fn get_best_score(level: usize, progress: &Persistent<LevelProgress>) -> Option<LevelScore> {
+    progress.best_scores.get(&level).cloned()
+}
+
+// Code of the level's active solution slot
 pub fn get_editor_state(level: usize, progress: &Persistent<LevelProgress>) -> Option<String> {
-    progress.editor_states.get(&level).cloned()
+    get_script_slots(level, progress)
+        .get(get_active_slot(level, progress))
+        .map(|slot| slot.code.clone())
 }
 
-pub fn is_level_available(level: usize, progress: &Persistent<LevelProgress>) -> bool {
-    level == 0 || progress.completed_levels.contains(&(level - 1))
+pub fn get_script_slots(level: usize, progress: &Persistent<LevelProgress>) -> &[NamedScript] {
+    progress
+        .editor_states
+        .get(&level)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+pub fn get_active_slot(level: usize, progress: &Persistent<LevelProgress>) -> usize {
+    progress.active_slots.get(&level).copied().unwrap_or(0)
+}
+
+pub fn get_notes(level: usize, progress: &Persistent<LevelProgress>) -> String {
+    progress.notes.get(&level).cloned().unwrap_or_default()
+}
+
+// Built-in levels open one after another; level-pack and custom levels are always open
+pub fn is_level_available(
+    level: usize,
+    level_manager: &LevelManager,
+    progress: &Persistent<LevelProgress>,
+) -> bool {
+    level == 0
+        || !level_manager.is_builtin_level(level)
+        || progress.completed_levels.contains(&(level - 1))
 }
 
 pub fn is_level_completed(level: usize, progress: &Persistent<LevelProgress>) -> bool {
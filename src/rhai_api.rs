@@ -1,6 +1,10 @@
 use bevy::prelude::*;
-use rhai::{Dynamic, Engine, Map as RhaiMap, Scope, AST};
-use std::sync::Arc;
+use rhai::{Dynamic, Engine, EvalAltResult, Map as RhaiMap, Position, Scope, AST};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::levels::{ControlScheme, LevelConfig};
 
 #[derive(Clone)]
 pub struct SimpleControl {
@@ -13,37 +17,176 @@ pub struct VectoredControl {
     pub gimbal: f32,
 }
 
+#[derive(Clone)]
+pub struct TranslateControl {
+    pub thrust: f32,
+    pub lateral: f32,
+}
+
 #[derive(Clone)]
 pub enum ControlOutput {
     Simple(SimpleControl),
     Vectored(VectoredControl),
+    Translate(TranslateControl),
 }
 
-#[derive(Clone)]
+// Script failure, with where in the source Rhai says it happened when it knows
+#[derive(Clone, Debug)]
+pub struct ScriptError {
+    pub message: String,
+    pub line: Option<usize>,
+    pub col: Option<usize>,
+}
+
+impl ScriptError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            line: None,
+            col: None,
+        }
+    }
+
+    fn at(message: impl Into<String>, position: Position) -> Self {
+        Self {
+            message: message.into(),
+            line: position.line(),
+            col: position.position(),
+        }
+    }
+
+    // Errors raised inside a function carry the call site on the outside; the line
+    // that actually failed is on the innermost error
+    fn runtime(error: &EvalAltResult) -> Self {
+        let position = match error.unwrap_inner().position() {
+            Position::NONE => error.position(),
+            position => position,
+        };
+        Self::at(format!("Runtime error: {}", error), position)
+    }
+}
+
+#[derive(Clone, Default)]
 pub struct LanderState {
     pub x: f32,
     pub y: f32,
+    pub agl: f32, // Height of the lander's base above the ground
     pub vx: f32,
     pub vy: f32,
     pub rotation: f32,
     pub angular_vel: f32,
     pub fuel: f32,
+    pub mass: f32, // Current total mass, dry plus fuel (kg)
+    pub wind: f32, // Scheduled horizontal wind force, not including turbulence (N)
+    pub g: f32,    // Gravity at the lander's position, negative like config["gravity"] (m/s²)
+    pub low_fuel: bool,
+    pub engine_heat: f32,
+    pub gimbal_limit: f32,
+    pub gimbal_rate_limit: f32,
+    pub time: f32, // Simulation time since reset (seconds), unaffected by pauses
+    pub jettisoned: bool,
+    pub target_x: f32, // Next waypoint, or the landing target once all are passed (m)
+    pub target_y: f32,
+    pub thrust_actual: f32, // Throttle the engine is at after the rate limit (0 to 1)
+    pub gimbal_actual: f32, // Gimbal angle after the rate limit (radians)
+    pub engine_ready: bool, // Past the startup delay, so thrust commands take effect
+    pub rcs_fuel: f32,      // Attitude thruster propellant left (kg)
+    pub landing_target: Vec2, // Final target, past all the waypoints (m)
+    pub downrange_axis: Vec2, // Unit vector from the initial position to the final target
+}
+
+// Names of the measured channels the sensor rate applies to
+const SENSOR_CHANNELS: [&str; 7] = ["x", "y", "agl", "vx", "vy", "rotation", "angular_vel"];
+
+// Tolerance on the sensor period so float drift in the step times doesn't skip a sample
+const SENSOR_PERIOD_TOLERANCE: f32 = 1e-4;
+
+// Last sensor reading handed to the script, held until the next sample is due
+#[derive(Clone)]
+struct SensorSample {
+    time: f32,
+    values: [f32; 7], // In SENSOR_CHANNELS order
 }
 
 #[derive(Resource)]
 pub struct ScriptEngine {
     engine: Arc<Engine>,
-    compiled_script: Option<Arc<AST>>,
-    pub error_message: Option<String>,
+    compiled_script: Option<Arc<AST>>, // Last good AST the simulation runs
+    scratch_script: Option<Arc<AST>>,  // Latest compile of the editor contents
+    scratch_source: Option<String>,    // Source the scratch compile was made from
+    pub compile_error: Option<ScriptError>,
+    pub error_message: Option<ScriptError>, // Runtime error from the running script
     pub control_type: ControlType,
     pub user_state: RhaiMap,
     pub console_buffer: Vec<String>,
+    sensor_rate: Option<f32>, // Sensor updates per second (None = every control step)
+    sensor_sample: Option<SensorSample>,
+    pid_state: PidState,
+    pub max_source_len: usize, // Longest script source (characters) the engine will compile
+    level_constants: RhaiMap,  // Read-only `config` map of the level's physics and limits
+    jettison_requested: Arc<AtomicBool>, // Set by the script's jettison() call
+    abort_request: Arc<Mutex<Option<String>>>, // Message passed to the script's abort() call
+    rcs_request: Arc<Mutex<f32>>, // Torque command passed to set_rcs() this tick
+    pub abort_message: Option<String>, // Set once the script aborted the run
+}
+
+// Default cap on script source size, keeps the compile path and editor responsive
+pub const MAX_SCRIPT_SOURCE_LEN: usize = 50_000;
+
+// (integral, previous error) of each pid() controller, keyed by the name the script
+// gives it. Shared with the registered function, so it lives behind a lock.
+type PidState = Arc<Mutex<HashMap<String, (f32, f32)>>>;
+
+// Number argument from a script, taking integers too: Rhai doesn't turn `1` into
+// `1.0` to match a float parameter
+fn script_number(value: &Dynamic) -> Result<f32, Box<EvalAltResult>> {
+    value
+        .as_float()
+        .or_else(|_| value.as_int().map(|int| int as f64))
+        .map(|number| number as f32)
+        .map_err(|type_name| format!("Expected a number, got {}", type_name).into())
+}
+
+// One update of a named PID controller
+fn pid_step(
+    pid_state: &PidState,
+    kp: f32,
+    ki: f32,
+    kd: f32,
+    error: f32,
+    dt: f32,
+    key: &str,
+) -> f32 {
+    let mut controllers = pid_state.lock().unwrap();
+    // The first call has no previous error, so it gets no derivative kick
+    let (integral, previous_error) = controllers.entry(key.to_string()).or_insert((0.0, error));
+
+    *integral += error * dt;
+    let derivative = if dt > 0.0 {
+        (error - *previous_error) / dt
+    } else {
+        0.0
+    };
+    *previous_error = error;
+
+    kp * error + ki * *integral + kd * derivative
 }
 
 #[derive(Clone)]
 pub enum ControlType {
     Simple,
     Vectored,
+    Translate,
+}
+
+impl From<&ControlScheme> for ControlType {
+    fn from(scheme: &ControlScheme) -> Self {
+        match scheme {
+            ControlScheme::VerticalOnly => ControlType::Simple,
+            ControlScheme::ThrustVector => ControlType::Vectored,
+            ControlScheme::Translate => ControlType::Translate,
+        }
+    }
 }
 
 impl Default for ScriptEngine {
@@ -52,16 +195,91 @@ impl Default for ScriptEngine {
 
         // Register console function that can handle any type
         let console_fn = move |x: Dynamic| {
-            let text = x.to_string();
-            // Add text directly to console buffer
-            CONSOLE_BUFFER.with(|buffer| {
-                buffer.borrow_mut().push(text);
-            });
+            console_print(format_console_value(&x));
             // Return () to satisfy Rhai
             Dynamic::UNIT
         };
         engine.register_fn("console", console_fn);
 
+        // console("vy", state["vy"]) prints "vy: -1.234"
+        engine.register_fn("console", |label: &str, value: Dynamic| {
+            console_print(format!("{}: {}", label, format_console_value(&value)));
+            Dynamic::UNIT
+        });
+
+        // console_fmt("x = {}, y = {}", [x, y]) fills each {} with the next value
+        engine.register_fn("console_fmt", |template: &str, args: rhai::Array| {
+            console_print(format_template(template, &args));
+            Dynamic::UNIT
+        });
+
+        // PID helper that keeps its integral and last error between ticks
+        let pid_state = PidState::default();
+        let pid_fn_state = pid_state.clone();
+        engine.register_fn(
+            "pid",
+            move |kp: Dynamic,
+                  ki: Dynamic,
+                  kd: Dynamic,
+                  error: Dynamic,
+                  dt: Dynamic,
+                  key: &str|
+                  -> Result<f64, Box<EvalAltResult>> {
+                Ok(pid_step(
+                    &pid_fn_state,
+                    script_number(&kp)?,
+                    script_number(&ki)?,
+                    script_number(&kd)?,
+                    script_number(&error)?,
+                    script_number(&dt)?,
+                    key,
+                ) as f64)
+            },
+        );
+
+        // Staging: the simulation drops the stage after this tick's control call
+        let jettison_requested = Arc::new(AtomicBool::new(false));
+        let jettison_flag = jettison_requested.clone();
+        engine.register_fn("jettison", move || {
+            jettison_flag.store(true, Ordering::Relaxed);
+        });
+
+        // Deliberate abort: the simulation ends the run as a failure with this message
+        let abort_request = Arc::new(Mutex::new(None));
+        let abort_slot = abort_request.clone();
+        engine.register_fn("abort", move |message: &str| {
+            *abort_slot.lock().unwrap() = Some(message.to_string());
+        });
+
+        // Attitude thrusters, commanded separately from the main engine each tick
+        let rcs_request = Arc::new(Mutex::new(0.0));
+        let rcs_slot = rcs_request.clone();
+        engine.register_fn("set_rcs", move |torque: f64| {
+            *rcs_slot.lock().unwrap() = torque as f32;
+        });
+        let rcs_slot = rcs_request.clone();
+        engine.register_fn("set_rcs", move |torque: i64| {
+            *rcs_slot.lock().unwrap() = torque as f32;
+        });
+
+        // Imperative alternative to returning the control output
+        // Integer overloads too, so set_thrust(1) works as well as set_thrust(1.0)
+        fn set_thrust(thrust: f32) {
+            SET_CONTROL.with(|set| set.borrow_mut().thrust = Some(thrust));
+        }
+        fn set_gimbal(gimbal: f32) {
+            SET_CONTROL.with(|set| set.borrow_mut().gimbal = Some(gimbal));
+        }
+        fn set_lateral(lateral: f32) {
+            SET_CONTROL.with(|set| set.borrow_mut().lateral = Some(lateral));
+        }
+        engine.register_fn("set_thrust", |thrust: f64| set_thrust(thrust as f32));
+        engine.register_fn("set_thrust", |thrust: i64| set_thrust(thrust as f32));
+        engine.register_fn("set_gimbal", |gimbal: f64| set_gimbal(gimbal as f32));
+        engine.register_fn("set_gimbal", |gimbal: i64| set_gimbal(gimbal as f32));
+        engine.register_fn("set_lateral", |lateral: f64| set_lateral(lateral as f32));
+        engine.register_fn("set_lateral", |lateral: i64| set_lateral(lateral as f32));
+
         // Disable unsafe operations
         engine.set_max_expr_depths(64, 64);
         engine.set_max_operations(100_000);
@@ -72,16 +290,92 @@ impl Default for ScriptEngine {
         Self {
             engine: Arc::new(engine),
             compiled_script: None,
+            scratch_script: None,
+            scratch_source: None,
+            compile_error: None,
             error_message: None,
             control_type: ControlType::Simple,
             user_state: RhaiMap::new(),
             console_buffer: Vec::new(),
+            sensor_rate: None,
+            sensor_sample: None,
+            pid_state,
+            max_source_len: MAX_SCRIPT_SOURCE_LEN,
+            level_constants: RhaiMap::new(),
+            jettison_requested,
+            abort_request,
+            rcs_request,
+            abort_message: None,
+        }
+    }
+}
+
+// Console lines kept before the oldest are dropped
+pub const CONSOLE_LINE_LIMIT: usize = 1000;
+
+// Significant figures floats are printed with in the console
+const CONSOLE_SIGNIFICANT_FIGURES: i32 = 4;
+
+// Add a line directly to the console buffer
+fn console_print(text: String) {
+    CONSOLE_BUFFER.with(|buffer| {
+        buffer.borrow_mut().push(text);
+    });
+}
+
+// Console text for a script value, with floats rounded to a readable precision
+fn format_console_value(value: &Dynamic) -> String {
+    match value.as_float() {
+        Ok(float) => format_significant(float),
+        Err(_) => value.to_string(),
+    }
+}
+
+fn format_significant(value: f64) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return value.to_string();
+    }
+    let magnitude = value.abs().log10().floor() as i32;
+    if !(-3..6).contains(&magnitude) {
+        // Very large or small numbers read better in scientific notation
+        return format!("{:.*e}", (CONSOLE_SIGNIFICANT_FIGURES - 1) as usize, value);
+    }
+    let decimals = (CONSOLE_SIGNIFICANT_FIGURES - 1 - magnitude).max(0) as usize;
+    format!("{:.*}", decimals, value)
+}
+
+// Replace each "{}" in the template with the next argument; extra placeholders stay as is
+fn format_template(template: &str, args: &[Dynamic]) -> String {
+    let mut args = args.iter();
+    let mut pieces = template.split("{}");
+    let mut text = pieces.next().unwrap_or_default().to_string();
+    for piece in pieces {
+        match args.next() {
+            Some(arg) => text.push_str(&format_console_value(arg)),
+            None => text.push_str("{}"),
         }
+        text.push_str(piece);
+    }
+    text
+}
+
+// Values passed to set_thrust() / set_gimbal() / set_lateral() during one control call
+#[derive(Default)]
+struct SetControl {
+    thrust: Option<f32>,
+    gimbal: Option<f32>,
+    lateral: Option<f32>,
+}
+
+impl SetControl {
+    fn any(&self) -> bool {
+        self.thrust.is_some() || self.gimbal.is_some() || self.lateral.is_some()
     }
 }
 
 thread_local! {
     static CONSOLE_BUFFER: std::cell::RefCell<Vec<String>> = const { std::cell::RefCell::new(Vec::new()) };
+    static SET_CONTROL: std::cell::RefCell<SetControl> = std::cell::RefCell::new(SetControl::default());
 }
 
 impl ScriptEngine {
@@ -89,70 +383,296 @@ impl ScriptEngine {
         self.control_type = control_type;
     }
 
+    // Set up the engine for a level: output format, sensors and the script's `config` map
+    pub fn set_level(&mut self, config: &LevelConfig) {
+        self.set_control_type(ControlType::from(&config.control_scheme));
+        self.set_sensor_rate(config.sensor_rate);
+
+        let mut constants = RhaiMap::new();
+        for (key, value) in [
+            ("max_thrust", config.physics.max_thrust),
+            ("gravity", config.physics.gravity),
+            ("dry_mass", config.physics.dry_mass),
+            ("isp", config.physics.isp),
+            ("moment_of_inertia", config.physics.moment_of_inertia),
+            ("thrust_moment_arm", config.physics.thrust_moment_arm),
+            ("thrust_rate", config.physics.thrust_rate),
+            ("rcs_max_torque", config.physics.rcs_max_torque),
+            ("vx_max", config.success.vx_max),
+            ("vy_max", config.success.vy_max),
+            ("final_angle", config.success.final_angle),
+        ] {
+            constants.insert(key.into(), Dynamic::from_float(value as f64));
+        }
+        self.level_constants = constants;
+    }
+
+    pub fn set_sensor_rate(&mut self, sensor_rate: Option<f32>) {
+        self.sensor_rate = sensor_rate;
+        self.sensor_sample = None;
+    }
+
+    // Sample-and-hold for the measured channels: take a new reading when the sensor
+    // period has passed (or the simulation was reset) and keep handing out the last
+    // one in between. Returns the values to show the script and whether they're fresh.
+    fn sense(&mut self, state: &LanderState) -> ([f32; 7], bool) {
+        let current = [
+            state.x,
+            state.y,
+            state.agl,
+            state.vx,
+            state.vy,
+            state.rotation,
+            state.angular_vel,
+        ];
+        let Some(rate) = self.sensor_rate.filter(|rate| *rate > 0.0) else {
+            return (current, true);
+        };
+
+        let due = match &self.sensor_sample {
+            Some(sample) => {
+                state.time < sample.time
+                    || state.time - sample.time >= 1.0 / rate - SENSOR_PERIOD_TOLERANCE
+            }
+            None => true,
+        };
+        if due {
+            self.sensor_sample = Some(SensorSample {
+                time: state.time,
+                values: current,
+            });
+            return (current, true);
+        }
+        (
+            self.sensor_sample.as_ref().map_or(current, |s| s.values),
+            false,
+        )
+    }
+
+    // Drop the script's persistent variables and any console output from the last run
+    pub fn clear_user_state(&mut self) {
+        self.user_state = RhaiMap::new();
+        self.console_buffer.clear();
+    }
+
+    // Forget everything the engine carries from tick to tick, for a fresh run
+    pub fn reset_runtime_state(&mut self) {
+        self.pid_state.lock().unwrap().clear();
+        self.sensor_sample = None;
+        self.jettison_requested.store(false, Ordering::Relaxed);
+        *self.abort_request.lock().unwrap() = None;
+        *self.rcs_request.lock().unwrap() = 0.0;
+        self.abort_message = None;
+    }
+
+    // Whether the script called jettison() since the last check
+    pub fn take_jettison_request(&mut self) -> bool {
+        self.jettison_requested.swap(false, Ordering::Relaxed)
+    }
+
+    // Attitude thruster command from the last control call (-1 to 1 of full torque)
+    pub fn take_rcs_command(&mut self) -> f32 {
+        std::mem::take(&mut *self.rcs_request.lock().unwrap())
+    }
+
+    // Compile the editor contents without touching the script the simulation runs.
+    // Recompiles only when the source changed since the last call.
     pub fn compile_script(&mut self, script: &str) -> Result<(), String> {
-        self.error_message = None;
-        match self.engine.compile(script) {
-            Ok(ast) => {
-                self.compiled_script = Some(Arc::new(ast));
-                Ok(())
+        if self.scratch_source.as_deref() != Some(script) {
+            self.scratch_source = Some(script.to_string());
+            let length = script.chars().count();
+            if length > self.max_source_len {
+                self.scratch_script = None;
+                let message = format!(
+                    "Script is too long to compile ({} characters, the limit is {}). \
+                     Try removing unused code or comments.",
+                    length, self.max_source_len
+                );
+                self.compile_error = Some(ScriptError::new(message.clone()));
+                return Err(message);
             }
-            Err(e) => {
-                let error = format!("Compilation error: {}", e);
-                self.error_message = Some(error.clone());
-                Err(error)
+            match self.engine.compile(script) {
+                Ok(ast) => {
+                    self.scratch_script = Some(Arc::new(ast));
+                    self.compile_error = None;
+                }
+                Err(e) => {
+                    self.scratch_script = None;
+                    self.compile_error = Some(ScriptError::at(
+                        format!("Compilation error: {}", e),
+                        e.position(),
+                    ));
+                }
+            }
+        }
+
+        match &self.compile_error {
+            Some(error) => Err(error.message.clone()),
+            None => Ok(()),
+        }
+    }
+
+    // The editor contents changed since the last compile
+    pub fn compile_pending(&self, script: &str) -> bool {
+        self.scratch_source.as_deref() != Some(script)
+    }
+
+    // Make the latest successful compile the script the simulation runs
+    pub fn activate_script(&mut self) -> bool {
+        match &self.scratch_script {
+            Some(ast) => {
+                self.compiled_script = Some(ast.clone());
+                self.error_message = None;
+                true
             }
+            None => false,
         }
     }
 
+    pub fn has_active_script(&self) -> bool {
+        self.compiled_script.is_some()
+    }
+
+    // Forget the last good script, e.g. when switching to another level
+    pub fn clear_active_script(&mut self) {
+        self.compiled_script = None;
+    }
+
     pub fn calculate_control(&mut self, state: LanderState) -> Option<ControlOutput> {
-        if let Some(ast) = &self.compiled_script {
+        if let Some(ast) = self.compiled_script.clone() {
             // Clear console buffer for this execution
             CONSOLE_BUFFER.with(|buffer| {
                 buffer.borrow_mut().clear();
             });
-            self.console_buffer.clear(); // Also clear the engine's buffer
+            SET_CONTROL.with(|set| set.take());
+            *self.rcs_request.lock().unwrap() = 0.0;
 
-            // Create state map
+            // Create state map, with the measured channels coming through the sensors
+            let (sensed, fresh) = self.sense(&state);
             let mut map = RhaiMap::new();
-            map.insert("x".into(), Dynamic::from_float(state.x as f64));
-            map.insert("y".into(), Dynamic::from_float(state.y as f64));
-            map.insert("vx".into(), Dynamic::from_float(state.vx as f64));
-            map.insert("vy".into(), Dynamic::from_float(state.vy as f64));
+            let mut fresh_map = RhaiMap::new();
+            for (channel, value) in SENSOR_CHANNELS.iter().zip(sensed) {
+                map.insert((*channel).into(), Dynamic::from_float(value as f64));
+                fresh_map.insert((*channel).into(), Dynamic::from_bool(fresh));
+            }
+            map.insert("fresh".into(), Dynamic::from_map(fresh_map));
+
+            // Distance still to go along the start-to-target line, and the sideways
+            // offset from it (positive 90° anticlockwise of down-range), from the sensed position
+            let offset = Vec2::new(sensed[0], sensed[1]) - state.landing_target;
+            let downrange = -offset.dot(state.downrange_axis);
+            let crossrange = offset.dot(state.downrange_axis.perp());
+            map.insert("downrange".into(), Dynamic::from_float(downrange as f64));
+            map.insert("crossrange".into(), Dynamic::from_float(crossrange as f64));
+            map.insert("t".into(), Dynamic::from_float(state.time as f64));
+            map.insert("jettisoned".into(), Dynamic::from_bool(state.jettisoned));
             map.insert(
-                "rotation".into(),
-                Dynamic::from_float(state.rotation as f64),
+                "engine_ready".into(),
+                Dynamic::from_bool(state.engine_ready),
             );
+            map.insert("fuel".into(), Dynamic::from_float(state.fuel as f64));
             map.insert(
-                "angular_vel".into(),
-                Dynamic::from_float(state.angular_vel as f64),
+                "rcs_fuel".into(),
+                Dynamic::from_float(state.rcs_fuel as f64),
+            );
+            map.insert("mass".into(), Dynamic::from_float(state.mass as f64));
+            map.insert(
+                "target_x".into(),
+                Dynamic::from_float(state.target_x as f64),
+            );
+            map.insert(
+                "target_y".into(),
+                Dynamic::from_float(state.target_y as f64),
+            );
+            map.insert(
+                "thrust_actual".into(),
+                Dynamic::from_float(state.thrust_actual as f64),
+            );
+            map.insert(
+                "gimbal_actual".into(),
+                Dynamic::from_float(state.gimbal_actual as f64),
+            );
+            map.insert("wind".into(), Dynamic::from_float(state.wind as f64));
+            map.insert("g".into(), Dynamic::from_float(state.g as f64));
+            map.insert("low_fuel".into(), Dynamic::from_bool(state.low_fuel));
+            map.insert(
+                "engine_heat".into(),
+                Dynamic::from_float(state.engine_heat as f64),
+            );
+            map.insert(
+                "gimbal_limit".into(),
+                Dynamic::from_float(state.gimbal_limit as f64),
+            );
+            map.insert(
+                "gimbal_rate_limit".into(),
+                Dynamic::from_float(state.gimbal_rate_limit as f64),
             );
-            map.insert("fuel".into(), Dynamic::from_float(state.fuel as f64));
 
             // Create scope with state and user_state
             let mut scope = Scope::new();
             scope.push("state", map.clone());
             scope.push_dynamic("user_state", Dynamic::from(self.user_state.clone()));
+            scope.push_constant("config", self.level_constants.clone());
 
             // First evaluate script to define functions
-            match self.engine.eval_ast_with_scope::<Dynamic>(&mut scope, ast) {
+            match self.engine.eval_ast_with_scope::<Dynamic>(&mut scope, &ast) {
                 Ok(_) => {
                     // Now call the control function
                     match self
                         .engine
-                        .call_fn::<Dynamic>(&mut scope, ast, "control", (map,))
+                        .call_fn::<Dynamic>(&mut scope, &ast, "control", (map,))
                     {
                         Ok(result) => {
-                            // Get console output and clear thread local buffer
+                            // Collect console output, stamped with the simulation time, until
+                            // the UI takes it
                             CONSOLE_BUFFER.with(|buffer| {
                                 let mut buffer = buffer.borrow_mut();
-                                self.console_buffer.extend(buffer.drain(..));
+                                self.console_buffer.extend(
+                                    buffer
+                                        .drain(..)
+                                        .map(|line| format!("[{:6.2}] {}", state.time, line)),
+                                );
                             });
+                            let excess =
+                                self.console_buffer.len().saturating_sub(CONSOLE_LINE_LIMIT);
+                            self.console_buffer.drain(..excess);
 
                             // Extract updated user_state
                             if let Some(new_state) = scope.get_value::<RhaiMap>("user_state") {
                                 self.user_state = new_state;
                             }
 
+                            // An abort overrides whatever the script returned
+                            if let Some(message) = self.abort_request.lock().unwrap().take() {
+                                self.abort_message = Some(message);
+                                return None;
+                            }
+
+                            // Values set through set_thrust() etc. take precedence over the
+                            // return value; unset ones default to zero. step_simulation clamps
+                            // both paths to the same actuator limits.
+                            let set = SET_CONTROL.with(|set| set.take());
+                            if set.any() {
+                                let thrust = set.thrust.unwrap_or(0.0);
+                                return Some(match self.control_type {
+                                    ControlType::Simple => {
+                                        ControlOutput::Simple(SimpleControl { thrust })
+                                    }
+                                    ControlType::Vectored => {
+                                        ControlOutput::Vectored(VectoredControl {
+                                            thrust,
+                                            gimbal: set.gimbal.unwrap_or(0.0),
+                                        })
+                                    }
+                                    ControlType::Translate => {
+                                        ControlOutput::Translate(TranslateControl {
+                                            thrust,
+                                            lateral: set.lateral.unwrap_or(0.0),
+                                        })
+                                    }
+                                });
+                            }
+
                             // Convert result to control output
                             match self.control_type {
                                 ControlType::Simple => match result.as_float() {
@@ -160,9 +680,9 @@ impl ScriptEngine {
                                         thrust: thrust as f32,
                                     })),
                                     Err(_) => {
-                                        self.error_message = Some(
-                                            "Control function must return a number (thrust)".into(),
-                                        );
+                                        self.error_message = Some(ScriptError::new(
+                                            "Control function must return a number (thrust)",
+                                        ));
                                         None
                                     }
                                 },
@@ -176,14 +696,36 @@ impl ScriptEngine {
                                                 }))
                                             }
                                             _ => {
-                                                self.error_message = Some("Control function must return [thrust, gimbal] as numbers".into());
+                                                self.error_message = Some(ScriptError::new("Control function must return [thrust, gimbal] as numbers"));
+                                                None
+                                            }
+                                        }
+                                    }
+                                    _ => {
+                                        self.error_message = Some(ScriptError::new(
+                                            "Control function must return [thrust, gimbal]",
+                                        ));
+                                        None
+                                    }
+                                },
+                                ControlType::Translate => match result.into_array() {
+                                    Ok(array) if array.len() == 2 => {
+                                        match (array[0].as_float(), array[1].as_float()) {
+                                            (Ok(thrust), Ok(lateral)) => {
+                                                Some(ControlOutput::Translate(TranslateControl {
+                                                    thrust: thrust as f32,
+                                                    lateral: lateral as f32,
+                                                }))
+                                            }
+                                            _ => {
+                                                self.error_message = Some(ScriptError::new("Control function must return [vertical_thrust, lateral_thrust] as numbers"));
                                                 None
                                             }
                                         }
                                     }
                                     _ => {
                                         self.error_message = Some(
-                                            "Control function must return [thrust, gimbal]".into(),
+                                            ScriptError::new("Control function must return [vertical_thrust, lateral_thrust]"),
                                         );
                                         None
                                     }
@@ -191,15 +733,13 @@ impl ScriptEngine {
                             }
                         }
                         Err(e) => {
-                            let error = format!("Runtime error: {}", e);
-                            self.error_message = Some(error);
+                            self.error_message = Some(ScriptError::runtime(&e));
                             None
                         }
                     }
                 }
                 Err(e) => {
-                    let error = format!("Runtime error: {}", e);
-                    self.error_message = Some(error);
+                    self.error_message = Some(ScriptError::runtime(&e));
                     None
                 }
             }
@@ -212,3 +752,115 @@ impl ScriptEngine {
         std::mem::take(&mut self.console_buffer)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine_with(script: &str) -> ScriptEngine {
+        let mut engine = ScriptEngine::default();
+        engine.compile_script(script).unwrap();
+        assert!(engine.activate_script());
+        engine
+    }
+
+    fn thrust_command(engine: &mut ScriptEngine, state: LanderState) -> f32 {
+        match engine.calculate_control(state) {
+            Some(ControlOutput::Simple(control)) => control.thrust,
+            _ => panic!("no control output: {:?}", engine.error_message),
+        }
+    }
+
+    #[test]
+    fn pid_drives_first_order_plant_to_setpoint() {
+        let mut engine = engine_with(
+            "fn control(state) { pid(2.0, 1.0, 0.0, 1.0 - state[\"x\"], 0.01, \"x\") }",
+        );
+
+        // x' = -x + u, integrated alongside the controller
+        let dt = 0.01;
+        let mut x = 0.0;
+        for step in 0..2000 {
+            let state = LanderState {
+                x,
+                time: step as f32 * dt,
+                ..Default::default()
+            };
+            let u = thrust_command(&mut engine, state);
+            x += (-x + u) * dt;
+        }
+        assert!((1.0 - x).abs() < 0.01, "x = {}", x);
+
+        // The integral carries the steady-state effort until the run is reset
+        assert!(engine.pid_state.lock().unwrap()["x"].0.abs() > 0.1);
+        engine.reset_runtime_state();
+        assert!(engine.pid_state.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn pid_takes_integer_arguments() {
+        let mut engine = engine_with("fn control(state) { pid(1, 0, 0.5, 2, 1, \"alt\") }");
+        assert_eq!(thrust_command(&mut engine, LanderState::default()), 2.0);
+    }
+
+    fn level() -> LevelConfig {
+        ron::from_str(include_str!("../assets/levels/level1.ron")).unwrap()
+    }
+
+    #[test]
+    fn config_map_matches_level() {
+        let config = level();
+        let mut engine = ScriptEngine::default();
+        engine.set_level(&config);
+        engine
+            .compile_script(
+                r#"
+                for key in ["max_thrust", "gravity", "dry_mass", "isp", "vx_max", "vy_max", "final_angle"] {
+                    user_state[key] = config[key];
+                }
+                fn control(state) { 0.0 }
+                "#,
+            )
+            .unwrap();
+        assert!(engine.activate_script());
+        thrust_command(&mut engine, LanderState::default());
+
+        for (key, expected) in [
+            ("max_thrust", config.physics.max_thrust),
+            ("gravity", config.physics.gravity),
+            ("dry_mass", config.physics.dry_mass),
+            ("isp", config.physics.isp),
+            ("vx_max", config.success.vx_max),
+            ("vy_max", config.success.vy_max),
+            ("final_angle", config.success.final_angle),
+        ] {
+            let value = engine.user_state[key].as_float().unwrap();
+            assert_eq!(value as f32, expected, "config[\"{}\"]", key);
+        }
+    }
+
+    #[test]
+    fn config_map_is_read_only() {
+        let mut engine = ScriptEngine::default();
+        engine.set_level(&level());
+        engine
+            .compile_script("config = #{}; fn control(state) { 0.0 }")
+            .unwrap();
+        assert!(engine.activate_script());
+        assert!(engine.calculate_control(LanderState::default()).is_none());
+        assert!(engine.error_message.is_some());
+    }
+
+    #[test]
+    fn set_thrust_takes_an_integer() {
+        let mut engine = engine_with("fn control(state) { set_thrust(1); 0.0 }");
+        assert_eq!(thrust_command(&mut engine, LanderState::default()), 1.0);
+    }
+
+    #[test]
+    fn set_rcs_takes_an_integer() {
+        let mut engine = engine_with("fn control(state) { set_rcs(-1); 0.0 }");
+        thrust_command(&mut engine, LanderState::default());
+        assert_eq!(engine.take_rcs_command(), -1.0);
+    }
+}
@@ -0,0 +1,97 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use std::io::{Read, Write};
+
+use crate::rhai_api::MAX_SCRIPT_SOURCE_LEN;
+
+// Level and editor code carried in a share link's query string
+pub struct SharedSolution {
+    pub level: usize,
+    pub code: Option<String>, // None when the link only names the level
+}
+
+// Gzip then URL-safe base64, which keeps a typical script to a few hundred characters
+// and needs no further escaping in a query string
+fn encode_code(code: &str) -> String {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(code.as_bytes())
+        .expect("writing to memory can't fail");
+    URL_SAFE_NO_PAD.encode(encoder.finish().expect("writing to memory can't fail"))
+}
+
+fn decode_code(param: &str) -> Result<String, String> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(param)
+        .map_err(|_| "The share link's code is damaged".to_string())?;
+    // Stop inflating just past the longest script the editor compiles (at most four
+    // UTF-8 bytes a character), so a crafted link can't expand to something huge
+    let max_bytes = MAX_SCRIPT_SOURCE_LEN as u64 * 4;
+    let mut inflated = Vec::new();
+    GzDecoder::new(bytes.as_slice())
+        .take(max_bytes + 1)
+        .read_to_end(&mut inflated)
+        .map_err(|_| "The share link's code is damaged".to_string())?;
+    let too_long = "The share link's code is longer than the script limit".to_string();
+    if inflated.len() as u64 > max_bytes {
+        return Err(too_long);
+    }
+    let code =
+        String::from_utf8(inflated).map_err(|_| "The share link's code is damaged".to_string())?;
+    if code.chars().count() > MAX_SCRIPT_SOURCE_LEN {
+        return Err(too_long);
+    }
+    Ok(code)
+}
+
+// Link to this page that opens the level with the code in the editor
+pub fn share_url(level: usize, code: &str) -> Option<String> {
+    let location = web_sys::window()?.location();
+    let origin = location.origin().ok()?;
+    let path = location.pathname().ok()?;
+    Some(format!(
+        "{}{}?level={}&code={}",
+        origin,
+        path,
+        level,
+        encode_code(code)
+    ))
+}
+
+// Drop the share link from the address bar once it's been opened, so a reload starts
+// from the saved state instead of importing the link again
+pub fn clear_page_url() {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let (Ok(history), Ok(path)) = (window.history(), window.location().pathname()) else {
+        return;
+    };
+    let _ = history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&path));
+}
+
+// The share link the page was opened with, if it was opened with one
+pub fn from_page_url() -> Option<Result<SharedSolution, String>> {
+    let search = web_sys::window()?.location().search().ok()?;
+
+    let mut level = None;
+    let mut code = None;
+    for pair in search.trim_start_matches('?').split('&') {
+        match pair.split_once('=') {
+            Some(("level", value)) => level = Some(value),
+            Some(("code", value)) => code = Some(value),
+            _ => {}
+        }
+    }
+
+    let level = level?;
+    Some(parse(level, code))
+}
+
+fn parse(level: &str, code: Option<&str>) -> Result<SharedSolution, String> {
+    let level = level
+        .parse()
+        .map_err(|_| format!("\"{}\" in the share link isn't a level number", level))?;
+    let code = code.map(decode_code).transpose()?;
+    Ok(SharedSolution { level, code })
+}
@@ -1,94 +1,790 @@
 use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use crate::{
-    constants::LANDER_BASE_OFFSET,
-    levels::{CurrentLevel, Reference},
+    constants::{LANDER_BASE_OFFSET, LANDER_HEIGHT, LANDER_WIDTH},
+    levels::{
+        CollisionShape, ControlScheme, CurrentLevel, Integrator, LevelConfig, Objective, Overheat,
+        Reference,
+    },
+    manual::{ControlSource, ManualInput},
+    persistence::{GhostSample, LevelScore},
     rhai_api::{ControlOutput, LanderState as ScriptLanderState, ScriptEngine},
+    ui::EditorState,
     visualization::CameraState,
 };
 
-// Control limits
-const MAX_GIMBAL_ANGLE: f32 = 0.4; // radians (~23 degrees)
-const MIN_GIMBAL_ANGLE: f32 = -0.4; // radians
+// Control limits; the rate limits and gimbal travel come from the level's physics
 const MAX_THRUST: f32 = 1.0;
 const MIN_THRUST: f32 = 0.0;
-const MAX_THRUST_CHANGE_RATE: f32 = 2.0; // Maximum thrust change per second
-const MAX_GIMBAL_RATE: f32 = 1.0; // Maximum gimbal angle change per second
 
-#[derive(Resource, Default)]
+// Seeded random source for stochastic simulation elements, so runs are repeatable
+#[derive(Clone)]
+pub struct SimRng(StdRng);
+
+impl SimRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Default for SimRng {
+    fn default() -> Self {
+        Self::from_seed(0)
+    }
+}
+
+// Frontal area used for drag: a disc as wide as the lander's base (m²)
+const DRAG_REFERENCE_AREA: f32 = std::f32::consts::PI * (LANDER_WIDTH / 2.0) * (LANDER_WIDTH / 2.0);
+
+// Offsets mixed into the run seed so each turbulence axis gets its own noise
+const TURBULENCE_X_STREAM: u64 = 0x0123_4567_89AB_CDEF;
+const TURBULENCE_Y_STREAM: u64 = 0xFEDC_BA98_7654_3210;
+// Offset for the initial-state dispersion, kept apart from the simulation RNG
+const DISPERSION_STREAM: u64 = 0x5DEE_CE66_D1CE_4E5B;
+
+// Random value in [-1, 1] for one lattice point of the noise (SplitMix64 hash)
+fn lattice_value(seed: u64, index: i64) -> f32 {
+    let mut z = seed.wrapping_add((index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 40) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0
+}
+
+// Smooth 1D value noise in [-1, 1]: random values at whole numbers, eased in between
+fn value_noise(seed: u64, t: f32) -> f32 {
+    let cell = t.floor();
+    let frac = t - cell;
+    let ease = frac * frac * (3.0 - 2.0 * frac);
+    let a = lattice_value(seed, cell as i64);
+    let b = lattice_value(seed, cell as i64 + 1);
+    a + (b - a) * ease
+}
+
+#[derive(Resource, Default, Clone)]
 pub struct LanderState {
-    pub position: Vec2,    // (x, y) position in meters
-    pub velocity: Vec2,    // (vx, vy) velocity in m/s
-    pub rotation: f32,     // rotation in radians
-    pub angular_vel: f32,  // angular velocity in rad/s
-    pub fuel: f32,         // kg
-    pub thrust_level: f32, // 0.0 to 1.0
-    pub gimbal_angle: f32, // radians
+    pub position: Vec2,      // (x, y) position in meters
+    pub velocity: Vec2,      // (vx, vy) velocity in m/s
+    pub rotation: f32,       // rotation in radians
+    pub angular_vel: f32,    // angular velocity in rad/s
+    pub fuel: f32,           // kg
+    pub thrust_level: f32,   // 0.0 to 1.0
+    pub gimbal_angle: f32,   // radians
+    pub lateral_thrust: f32, // -1.0 to 1.0, lateral RCS throttle (Translate scheme)
     pub crashed: bool,
     pub landed: bool,
-    pub success_timer: f32, // Time spent meeting success criteria
-    pub stabilizing: bool,  // True when meeting conditions but not yet complete
+    pub success_timer: f32,      // Time spent meeting success criteria
+    pub stabilizing: bool,       // True when meeting conditions but not yet complete
+    pub elapsed: f32,            // Simulation time since reset (seconds)
+    pub engine_heat: f32,        // Accumulated engine bell heat
+    pub engine_overheated: bool, // Engine cut off until it cools down
+    pub touchdown_velocity: Option<Vec2>, // Velocity at the moment of first ground contact
+    pub assisted: bool,          // Precision assist slowed down part of this run
+    pub seed: u64,               // Seed the run's random elements start from
+    pub accumulator: f32,        // Real time not yet consumed by fixed physics steps
+    pub attitude_assist: bool,   // Fly the gimbal for the script on vectored levels
+    pub dispersion: bool,        // Perturb the initial state by the level's dispersion
+    pub jettisoned: bool,        // The droppable stage has separated
+    pub success_checks: SuccessChecks, // Which success criteria held at the last step
+    pub manual_control: Option<ControlOutput>, // Player's commands, used instead of the script
+    pub waypoint_index: usize,   // Next waypoint to pass; equal to the count once all are done
+    pub ignition_timer: f32,     // Time thrust has been commanded since the engine was last off (s)
+    pub rcs_fuel: f32,           // Attitude thruster propellant left (kg)
+    pub rcs_level: f32,          // -1.0 to 1.0, attitude thruster torque command being applied
+    pub leg_compression: f32,    // How far the sprung landing legs are pushed in (m)
+    pub rng: SimRng,
+}
+
+// Result of each success criterion; criteria the level doesn't use always pass
+#[derive(Default, Clone, Copy)]
+pub struct SuccessChecks {
+    pub position: bool,
+    pub vertical_speed: bool,
+    pub horizontal_speed: bool,
+    pub attitude: bool,
+    pub rate: bool,
+    pub descent: bool,
+    pub waypoints: bool,
+}
+
+impl SuccessChecks {
+    pub fn all(&self) -> bool {
+        self.position
+            && self.vertical_speed
+            && self.horizontal_speed
+            && self.attitude
+            && self.rate
+            && self.descent
+            && self.waypoints
+    }
+}
+
+// Snapshot of the lander recorded every simulation step
+#[derive(Clone, Copy)]
+pub struct TrajectorySample {
+    pub t: f32,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub rotation: f32,
+    pub angular_vel: f32,
+    pub fuel: f32,
+    pub thrust_level: f32,
+    pub gimbal_angle: f32,
+}
+
+impl TrajectorySample {
+    pub fn from_state(state: &LanderState) -> Self {
+        Self {
+            t: state.elapsed,
+            position: state.position,
+            velocity: state.velocity,
+            rotation: state.rotation,
+            angular_vel: state.angular_vel,
+            fuel: state.fuel,
+            thrust_level: state.thrust_level,
+            gimbal_angle: state.gimbal_angle,
+        }
+    }
+
+    pub fn apply_to(&self, state: &mut LanderState) {
+        state.elapsed = self.t;
+        state.position = self.position;
+        state.velocity = self.velocity;
+        state.rotation = self.rotation;
+        state.angular_vel = self.angular_vel;
+        state.fuel = self.fuel;
+        state.thrust_level = self.thrust_level;
+        state.gimbal_angle = self.gimbal_angle;
+    }
+}
+
+// Recorded history of the current run
+#[derive(Resource, Default)]
+pub struct Trajectory {
+    pub samples: Vec<TrajectorySample>,
+    pub attempt_saved: bool, // The finished run was added to the attempt history
+}
+
+impl Trajectory {
+    pub fn record(&mut self, state: &LanderState) {
+        self.samples.push(TrajectorySample::from_state(state));
+    }
+
+    // (x, y) path with at most `max_points` evenly spaced samples
+    pub fn downsampled_path(&self, max_points: usize) -> Vec<(f32, f32)> {
+        let step = self.samples.len().div_ceil(max_points).max(1);
+        self.samples
+            .iter()
+            .step_by(step)
+            .map(|sample| (sample.position.x, sample.position.y))
+            .collect()
+    }
+
+    // Samples at least `interval` seconds apart, plus the final one, for the ghost replay
+    pub fn ghost_samples(&self, interval: f32) -> Vec<GhostSample> {
+        let mut ghost: Vec<GhostSample> = Vec::new();
+        for (i, sample) in self.samples.iter().enumerate() {
+            let is_last = i + 1 == self.samples.len();
+            if !is_last
+                && ghost
+                    .last()
+                    .is_some_and(|last| sample.t - last.t < interval)
+            {
+                continue;
+            }
+            ghost.push(GhostSample {
+                t: sample.t,
+                x: sample.position.x,
+                y: sample.position.y,
+                rotation: sample.rotation,
+                thrust: sample.thrust_level,
+            });
+        }
+        ghost
+    }
+}
+
+// Longest telemetry log kept; the oldest samples are dropped in chunks past this
+const MAX_TELEMETRY_SAMPLES: usize = 36_000;
+const TELEMETRY_DROP_CHUNK: usize = 3_600;
+
+// Per-step values for the telemetry plots, appended by simulation_system
+#[derive(Clone, Copy)]
+pub struct TelemetrySample {
+    pub t: f32,
+    pub x: f32,
+    pub y: f32,
+    pub vx: f32,
+    pub vy: f32,
+    pub rotation: f32,
+    pub thrust: f32,
+    pub gimbal: f32,
+    pub fuel: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct TelemetryLog {
+    pub samples: Vec<TelemetrySample>,
+}
+
+impl TelemetryLog {
+    pub fn record(&mut self, state: &LanderState) {
+        // Time going backwards means the run was reset since the last sample
+        if self
+            .samples
+            .last()
+            .is_some_and(|last| state.elapsed < last.t)
+        {
+            self.samples.clear();
+        }
+        if self.samples.len() >= MAX_TELEMETRY_SAMPLES {
+            self.samples.drain(..TELEMETRY_DROP_CHUNK);
+        }
+        self.samples.push(TelemetrySample {
+            t: state.elapsed,
+            x: state.position.x,
+            y: state.position.y,
+            vx: state.velocity.x,
+            vy: state.velocity.y,
+            rotation: state.rotation,
+            thrust: state.thrust_level,
+            gimbal: state.gimbal_angle,
+            fuel: state.fuel,
+        });
+    }
+
+    // The whole log as CSV, one row per physics step
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("t,x,y,vx,vy,rotation,thrust,gimbal,fuel\n");
+        for s in &self.samples {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{}\n",
+                s.t, s.x, s.y, s.vx, s.vy, s.rotation, s.thrust, s.gimbal, s.fuel
+            ));
+        }
+        csv
+    }
+
+    // Samples up to a simulation time, thinned to at most `max_points`
+    pub fn downsampled(&self, up_to: f32, max_points: usize) -> Vec<TelemetrySample> {
+        let end = self.samples.partition_point(|sample| sample.t <= up_to);
+        let step = end.div_ceil(max_points).max(1);
+        self.samples[..end].iter().step_by(step).copied().collect()
+    }
+}
+
+// Review-time playback over the recorded trajectory. While active, `LanderState`
+// is driven from the recorded samples instead of the physics.
+#[derive(Resource, Default)]
+pub struct TrajectoryPlayback {
+    pub active: bool,
+    pub index: usize,
+    pub reversing: bool,
+    pub playing: bool,               // Stepping forwards in real time
+    live_state: Option<LanderState>, // State at the moment review started
+}
+
+impl TrajectoryPlayback {
+    pub fn start(&mut self, state: &LanderState, trajectory: &Trajectory) {
+        if self.active || trajectory.samples.is_empty() {
+            return;
+        }
+        self.active = true;
+        self.reversing = false;
+        self.playing = false;
+        self.index = trajectory.samples.len() - 1;
+        self.live_state = Some(state.clone());
+    }
+
+    // Leave review mode and put back the state the run ended in
+    pub fn stop(&mut self, state: &mut LanderState) {
+        if let Some(live_state) = self.live_state.take() {
+            *state = live_state;
+        }
+        self.active = false;
+        self.reversing = false;
+        self.playing = false;
+    }
+
+    // Leave review mode without restoring anything, e.g. because the run was reset
+    pub fn cancel(&mut self) {
+        self.live_state = None;
+        self.active = false;
+        self.reversing = false;
+        self.playing = false;
+    }
+
+    pub fn seek(&mut self, index: usize, state: &mut LanderState, trajectory: &Trajectory) {
+        if let Some(sample) = trajectory.samples.get(index) {
+            self.index = index;
+            sample.apply_to(state);
+            // Show the lander as it was at that moment rather than the final outcome
+            state.crashed = false;
+            state.landed = false;
+            state.stabilizing = false;
+        }
+    }
+}
+
+// Steps through the recorded trajectory in real time while rewinding or playing,
+// stopping at either end
+pub fn trajectory_playback_system(
+    time: Res<Time>,
+    mut playback: ResMut<TrajectoryPlayback>,
+    mut state: ResMut<LanderState>,
+    trajectory: Res<Trajectory>,
+) {
+    if !playback.active || !(playback.reversing || playback.playing) {
+        return;
+    }
+
+    let Some(current) = trajectory.samples.get(playback.index) else {
+        playback.reversing = false;
+        playback.playing = false;
+        return;
+    };
+
+    let last_index = trajectory.samples.len() - 1;
+    let mut index = playback.index;
+    let finished = if playback.reversing {
+        let target_t = current.t - time.delta_secs();
+        while index > 0 && trajectory.samples[index].t > target_t {
+            index -= 1;
+        }
+        index == 0
+    } else {
+        let target_t = current.t + time.delta_secs();
+        while index < last_index && trajectory.samples[index].t < target_t {
+            index += 1;
+        }
+        index == last_index
+    };
+    playback.seek(index, &mut state, &trajectory);
+
+    if finished {
+        playback.reversing = false;
+        playback.playing = false;
+    }
+}
+
+impl LanderState {
+    // Whether the engine would produce thrust if commanded now
+    pub fn engine_ready(&self, config: &LevelConfig) -> bool {
+        self.ignition_timer >= config.physics.startup_delay
+    }
+
+    // Sideways and vertical velocity relative to the local ground: world x and y on
+    // flat ground, tangential and radial in planet-center mode
+    pub fn local_velocity(&self, config: &LevelConfig) -> (f32, f32) {
+        self.local_components(config, self.velocity)
+    }
+
+    // Sideways and vertical parts of a world-frame vector at the lander's position
+    pub fn local_components(&self, config: &LevelConfig, vector: Vec2) -> (f32, f32) {
+        let up = config.physics.local_up(self.position);
+        (vector.dot(-up.perp()), vector.dot(up))
+    }
+
+    // Rotation from the local vertical, wrapped to ±π
+    pub fn tilt(&self, config: &LevelConfig) -> f32 {
+        let up = config.physics.local_up(self.position);
+        let tilt = self.rotation - (-up.x).atan2(up.y);
+        tilt.sin().atan2(tilt.cos())
+    }
+
+    // Rotation away from the level's required final angle, wrapped to ±π
+    pub fn attitude_error(&self, config: &LevelConfig) -> f32 {
+        let error = self.tilt(config) - config.success.final_angle;
+        error.sin().atan2(error.cos())
+    }
+
+    // Next waypoint, or the level's final target once they're all passed
+    pub fn current_target(&self, config: &LevelConfig) -> Vec2 {
+        config
+            .waypoints
+            .get(self.waypoint_index)
+            .map_or(config.final_target(), |waypoint| {
+                Vec2::new(waypoint.x, waypoint.y)
+            })
+    }
+
+    pub fn total_mass(&self, config: &LevelConfig) -> f32 {
+        self.dry_mass(config) + self.fuel
+    }
+
+    // Moment of inertia scaled with the current mass, so the lander turns more
+    // easily as it burns fuel
+    pub fn moment_of_inertia(&self, config: &LevelConfig) -> f32 {
+        let full_mass = config.physics.dry_mass + config.initial.initial_fuel;
+        if full_mass <= 0.0 {
+            return config.physics.moment_of_inertia;
+        }
+        config.physics.moment_of_inertia * self.total_mass(config) / full_mass
+    }
+
+    // Thrust moment arm, growing by the center of mass shift as the tanks empty
+    pub fn thrust_moment_arm(&self, config: &LevelConfig) -> f32 {
+        let fuel_used = if config.initial.initial_fuel > 0.0 {
+            (1.0 - self.fuel / config.initial.initial_fuel).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        config.physics.thrust_moment_arm + config.physics.com_shift * fuel_used
+    }
+
+    // Dry mass, without the stage's structure once it has been jettisoned
+    pub fn dry_mass(&self, config: &LevelConfig) -> f32 {
+        match (&config.physics.stage, self.jettisoned) {
+            (Some(stage), true) => config.physics.dry_mass - stage.dry_mass,
+            _ => config.physics.dry_mass,
+        }
+    }
+
+    pub fn thrust_force(&self, config: &LevelConfig) -> Vec2 {
+        // When rotation is 0 (pointing up):
+        //   - thrust should be upward
+        //   - gimbal rotates this direction
+        let thrust_direction = -self.rotation - self.gimbal_angle;
+
+        Vec2::new(
+            thrust_direction.sin() * self.thrust_level * config.physics.max_thrust,
+            thrust_direction.cos() * self.thrust_level * config.physics.max_thrust,
+        )
+    }
+
+    // Lateral RCS force along the lander's body x-axis
+    pub fn lateral_force(&self, config: &LevelConfig) -> Vec2 {
+        Vec2::new(self.rotation.cos(), self.rotation.sin())
+            * self.lateral_thrust
+            * config.physics.max_lateral_thrust
+    }
+
+    // Push of the sprung landing legs against the ground, along the local vertical, and
+    // the friction that stops the lander sliding while it stands on them
+    pub fn leg_force(&self, config: &LevelConfig) -> Vec2 {
+        let physics = &config.physics;
+        let compression = -ground_clearance(self, config);
+        if physics.leg_stiffness <= 0.0 || compression <= 0.0 {
+            return Vec2::ZERO;
+        }
+        let up = physics.local_up(self.position);
+        let closing_speed = -self.velocity.dot(up);
+        // The legs only push, they never pull the lander down onto the ground
+        let normal =
+            (physics.leg_stiffness * compression + physics.leg_damping * closing_speed).max(0.0);
+        let sliding = self.velocity - up * self.velocity.dot(up);
+        up * normal - sliding * physics.leg_damping
+    }
+
+    // Gravity force, straight down or toward the planet center
+    pub fn gravity_force(&self, config: &LevelConfig) -> Vec2 {
+        config.physics.gravity_at(self.position) * self.total_mass(config)
+    }
+
+    // Aerodynamic drag, always directly against the velocity so it only removes energy
+    pub fn drag_force(&self, config: &LevelConfig) -> Vec2 {
+        let physics = &config.physics;
+        if physics.drag_coefficient <= 0.0 {
+            return Vec2::ZERO;
+        }
+        -0.5 * physics.air_density
+            * physics.drag_coefficient
+            * DRAG_REFERENCE_AREA
+            * self.velocity.length()
+            * self.velocity
+    }
+
+    // Horizontal wind force from the level's wind schedule, without turbulence (N)
+    pub fn steady_wind(&self, config: &LevelConfig) -> f32 {
+        config
+            .wind
+            .as_ref()
+            .map_or(0.0, |wind| wind.force_at(self.elapsed))
+    }
+
+    // Wind plus turbulence force at the current simulation time, zero when the level has none
+    pub fn wind_force(&self, config: &LevelConfig) -> Vec2 {
+        let turbulence = match &config.physics.turbulence {
+            Some(turbulence) if turbulence.correlation_time > 0.0 => {
+                let t = self.elapsed / turbulence.correlation_time;
+                Vec2::new(
+                    value_noise(self.seed ^ TURBULENCE_X_STREAM, t),
+                    value_noise(self.seed ^ TURBULENCE_Y_STREAM, t),
+                ) * turbulence.amplitude
+            }
+            _ => Vec2::ZERO,
+        };
+        Vec2::new(self.steady_wind(config), 0.0) + turbulence
+    }
+
+    pub fn is_low_fuel(&self, config: &LevelConfig) -> bool {
+        config.low_fuel_fraction > 0.0
+            && self.fuel <= config.low_fuel_fraction * config.initial.initial_fuel
+    }
+
+    // Engine heat as a fraction of the overheat limit (0 when the level has no thermal model)
+    pub fn heat_fraction(&self, config: &LevelConfig) -> f32 {
+        match &config.physics.overheat {
+            Some(overheat) if overheat.max_heat > 0.0 => self.engine_heat / overheat.max_heat,
+            _ => 0.0,
+        }
+    }
+}
+
+// Fraction of the max heat the engine must cool to before it can relight
+const OVERHEAT_RESTART_FRACTION: f32 = 0.5;
+
+// Integrate the engine bell heat for the applied throttle and latch the overheat cutoff.
+// Returns true when the engine overheated on a level that treats this as failure.
+fn update_engine_heat(state: &mut LanderState, overheat: &Overheat, dt: f32) -> bool {
+    let excess = state.thrust_level - overheat.throttle_threshold;
+    if excess > 0.0 {
+        state.engine_heat += excess * overheat.heating_rate * dt;
+    } else {
+        state.engine_heat = (state.engine_heat + excess * overheat.cooling_rate * dt).max(0.0);
+    }
+
+    if state.engine_heat >= overheat.max_heat {
+        state.engine_overheated = true;
+        return overheat.fail_on_overheat;
+    }
+    if state.engine_overheated && state.engine_heat <= overheat.max_heat * OVERHEAT_RESTART_FRACTION
+    {
+        state.engine_overheated = false;
+    }
+    false
 }
 
 // Constants for rotational dynamics
-const MOMENT_OF_INERTIA: f32 = 100.0; // kg·m²
 const ANGULAR_DAMPING: f32 = 0.0; // artificial damping coefficient
 
-fn check_success_conditions(state: &LanderState, level: &CurrentLevel) -> bool {
-    let config = &level.config;
+// Corners of the lander triangle in world coordinates (top, bottom left, bottom right)
+pub fn lander_footprint(state: &LanderState) -> [Vec2; 3] {
+    let half_height = LANDER_HEIGHT / 2.0;
+    let half_width = LANDER_WIDTH / 2.0;
+    let rotation = Mat2::from_angle(state.rotation);
+
+    [
+        Vec2::new(0.0, half_height),
+        Vec2::new(-half_width, -half_height),
+        Vec2::new(half_width, -half_height),
+    ]
+    .map(|corner| state.position + rotation * corner)
+}
+
+// Separating-axis test between the lander triangle and an axis-aligned box. Touching
+// counts as overlapping, so grazing an edge is a hit.
+fn footprint_overlaps_box(footprint: &[Vec2; 3], min: Vec2, max: Vec2) -> bool {
+    let box_corners = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)];
+    let edge_normals = (0..3).map(|i| (footprint[(i + 1) % 3] - footprint[i]).perp());
+    let project = |points: &[Vec2], axis: Vec2| {
+        points
+            .iter()
+            .map(|point| point.dot(axis))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), d| {
+                (lo.min(d), hi.max(d))
+            })
+    };
+    [Vec2::X, Vec2::Y]
+        .into_iter()
+        .chain(edge_normals)
+        .all(|axis| {
+            let (a_min, a_max) = project(footprint, axis);
+            let (b_min, b_max) = project(&box_corners, axis);
+            a_min <= b_max && b_min <= a_max
+        })
+}
 
-    // Check velocity constraints
-    let speed_ok = state.velocity.x.abs() <= config.success.vx_max
-        && state.velocity.y.abs() <= config.success.vy_max;
+// Height of the lowest part of the lander above the ground (negative when penetrating)
+fn ground_clearance(state: &LanderState, config: &LevelConfig) -> f32 {
+    match config.failure.collision_shape {
+        CollisionShape::Point => config.height_above_ground(state.position) - LANDER_BASE_OFFSET,
+        CollisionShape::Footprint => lander_footprint(state)
+            .iter()
+            .map(|corner| config.height_above_ground(*corner))
+            .fold(f32::INFINITY, f32::min),
+    }
+}
 
-    // Check angle constraints
-    let angle_ok =
-        (state.rotation - config.success.final_angle).abs() <= config.success.angle_tolerance;
+fn check_success_conditions(state: &LanderState, config: &LevelConfig) -> SuccessChecks {
+    // Check velocity constraints, against the local ground
+    let (lateral_velocity, vertical_velocity) = state.local_velocity(config);
+    let horizontal_speed = lateral_velocity.abs() <= config.success.vx_max;
+    let vertical_speed = vertical_velocity.abs() <= config.success.vy_max;
+
+    // Check angle constraints, measured from the local vertical
+    let attitude = state.attitude_error(config).abs() <= config.success.angle_tolerance;
+
+    // Check the rotation has been nulled
+    let rate = match config.success.max_angular_rate {
+        Some(max_rate) => state.angular_vel.abs() <= max_rate,
+        None => true,
+    };
 
     // Check position constraints
-    let position_ok = match config.success.position_box.reference {
-        Reference::Initial => {
-            // For initial-reference boxes (like hover), always check position
-            let initial_pos = Vec2::new(config.initial.x0, config.initial.y0);
-            let rel_pos = state.position - initial_pos;
-            rel_pos.x >= config.success.position_box.x_min
-                && rel_pos.x <= config.success.position_box.x_max
-                && rel_pos.y >= config.success.position_box.y_min
-                && rel_pos.y <= config.success.position_box.y_max
-        }
-        Reference::Absolute => {
-            if state.position.y <= LANDER_BASE_OFFSET + 0.1 {
-                // Only check absolute position constraints when on/near ground
-                state.position.x >= config.success.position_box.x_min
-                    && state.position.x <= config.success.position_box.x_max
-                    && state.position.y >= config.success.position_box.y_min
-                    && state.position.y <= config.success.position_box.y_max
-            } else {
-                // When in air, only check speed and angle
-                false
+    let position = if let Objective::Ascent {
+        target_altitude,
+        altitude_tolerance,
+    } = config.success.objective
+    {
+        // Ascent: reach the target altitude in flight, no ground contact needed
+        (config.physics.altitude(state.position) - target_altitude).abs() <= altitude_tolerance
+            && state.position.x >= config.success.position_box.x_min
+            && state.position.x <= config.success.position_box.x_max
+    } else {
+        match config.success.position_box.reference {
+            Reference::Initial => {
+                // For initial-reference boxes (like hover), always check position
+                let initial_pos = Vec2::new(config.initial.x0, config.initial.y0);
+                let rel_pos = state.position - initial_pos;
+                rel_pos.x >= config.success.position_box.x_min
+                    && rel_pos.x <= config.success.position_box.x_max
+                    && rel_pos.y >= config.success.position_box.y_min
+                    && rel_pos.y <= config.success.position_box.y_max
+            }
+            Reference::Absolute => {
+                if ground_clearance(state, config) <= 0.1 {
+                    // Only check absolute position constraints when on/near ground
+                    let altitude = config.height_above_ground(state.position);
+                    state.position.x >= config.success.position_box.x_min
+                        && state.position.x <= config.success.position_box.x_max
+                        && altitude >= config.success.position_box.y_min
+                        && altitude <= config.success.position_box.y_max
+                } else {
+                    // When in air, only check speed and angle
+                    false
+                }
             }
         }
     };
 
-    speed_ok && position_ok && angle_ok
+    // Check the lander came down mostly vertically rather than drifting sideways,
+    // measured from the local vertical
+    let descent = match (config.success.max_velocity_angle, state.touchdown_velocity) {
+        (Some(max_angle), Some(touchdown)) if touchdown.length() > f32::EPSILON => {
+            let (lateral, vertical) = state.local_components(config, touchdown);
+            lateral.atan2(-vertical).abs() <= max_angle
+        }
+        _ => true,
+    };
+
+    // Every waypoint has to be passed first
+    let waypoints = state.waypoint_index >= config.waypoints.len();
+
+    SuccessChecks {
+        position,
+        vertical_speed,
+        horizontal_speed,
+        attitude,
+        rate,
+        descent,
+        waypoints,
+    }
 }
 
-fn check_failure_conditions(state: &LanderState, level: &CurrentLevel) -> bool {
-    let config = &level.config;
+// Move on to the next waypoint while the lander is within the current one's radius
+// (and below its speed limit, if it has one)
+fn advance_waypoints(state: &mut LanderState, config: &LevelConfig) {
+    while let Some(waypoint) = config.waypoints.get(state.waypoint_index) {
+        let within = state.position.distance(Vec2::new(waypoint.x, waypoint.y)) <= waypoint.radius;
+        let slow_enough = waypoint
+            .max_speed
+            .is_none_or(|max_speed| state.velocity.length() <= max_speed);
+        if !(within && slow_enough) {
+            break;
+        }
+        state.waypoint_index += 1;
+    }
+}
+
+// Slowest time scale the precision assist slows the simulation to
+const PRECISION_MIN_TIME_SCALE: f32 = 0.25;
+// Distance from the target box at which the precision assist starts slowing down (meters)
+const PRECISION_ASSIST_RADIUS: f32 = 10.0;
+
+// Time scale for the precision assist: 1.0 far from the success envelope, easing
+// down to PRECISION_MIN_TIME_SCALE as the lander gets close to it and slows down
+pub fn precision_time_scale(state: &LanderState, config: &LevelConfig) -> f32 {
+    let position_box = &config.success.position_box;
+    let rel_pos = match position_box.reference {
+        Reference::Initial => state.position - Vec2::new(config.initial.x0, config.initial.y0),
+        Reference::Absolute => state.position,
+    };
+
+    // Distance from the target box, zero when inside it
+    let dx = (position_box.x_min - rel_pos.x)
+        .max(rel_pos.x - position_box.x_max)
+        .max(0.0);
+    // Heights measured the way the success check measures them
+    let dy = match config.success.objective {
+        Objective::Ascent {
+            target_altitude,
+            altitude_tolerance,
+        } => {
+            let altitude = config.physics.altitude(state.position);
+            ((altitude - target_altitude).abs() - altitude_tolerance).max(0.0)
+        }
+        Objective::Landing => {
+            let height = match position_box.reference {
+                Reference::Initial => rel_pos.y,
+                Reference::Absolute => config.height_above_ground(state.position),
+            };
+            (position_box.y_min - height)
+                .max(height - position_box.y_max)
+                .max(0.0)
+        }
+    };
+    let distance = Vec2::new(dx, dy).length();
+
+    // How far over the velocity limits we are (1.0 = right at the limit), against the
+    // local ground
+    let (lateral_velocity, vertical_velocity) = state.local_velocity(config);
+    let speed_ratio = (lateral_velocity.abs() / config.success.vx_max.max(f32::EPSILON))
+        .max(vertical_velocity.abs() / config.success.vy_max.max(f32::EPSILON));
+
+    let position_closeness = (1.0 - distance / PRECISION_ASSIST_RADIUS).clamp(0.0, 1.0);
+    let speed_closeness = (2.0 - speed_ratio).clamp(0.0, 1.0);
+    let closeness = position_closeness * speed_closeness;
 
+    1.0 - (1.0 - PRECISION_MIN_TIME_SCALE) * closeness
+}
+
+fn check_failure_conditions(state: &LanderState, config: &LevelConfig) -> bool {
     // Check ground collision based on the flag
-    if state.position.y <= LANDER_BASE_OFFSET {
+    if ground_clearance(state, config) <= 0.0 {
         if config.failure.ground_collision {
             // If ground_collision flag is true, any contact is failure
             return true;
         } else {
-            // Otherwise, check if landing was too hard
-            let hard_landing = state.velocity.x.abs() > config.success.vx_max * 1.5
-                || state.velocity.y.abs() > config.success.vy_max * 1.5;
+            // Otherwise, check if landing was too hard. Sprung legs absorb the impact
+            // and only a touchdown that bottoms them out is too hard.
+            let hard_landing = if config.physics.leg_stiffness > 0.0 {
+                -ground_clearance(state, config) > config.physics.leg_stroke
+            } else {
+                let (lateral_velocity, vertical_velocity) = state.local_velocity(config);
+                lateral_velocity.abs() > config.success.vx_max * 1.5
+                    || vertical_velocity.abs() > config.success.vy_max * 1.5
+            };
             if hard_landing {
                 return true;
             }
         }
+
+        // Touching down leaning over or skidding sideways tips the lander over
+        let (lateral_velocity, _) = state.local_velocity(config);
+        if state.tilt(config).abs() > config.success.max_touchdown_tilt
+            || lateral_velocity.abs() > config.success.max_lateral_speed
+        {
+            return true;
+        }
+
+        // Touching down on a steep slope tips the lander over
+        if let Some(terrain) = config.flat_terrain() {
+            if terrain.slope_at(state.position.x).abs() > terrain.max_landing_slope {
+                return true;
+            }
+        }
     }
 
     // Check out-of-bounds if defined
@@ -98,214 +794,1019 @@ fn check_failure_conditions(state: &LanderState, level: &CurrentLevel) -> bool {
             Reference::Initial => Vec2::new(config.initial.x0, config.initial.y0),
         };
 
-        let rel_pos = state.position - reference_pos;
-        if rel_pos.x < bounds.x_min
-            || rel_pos.x > bounds.x_max
-            || rel_pos.y < bounds.y_min
-            || rel_pos.y > bounds.y_max
-        {
+        let points = match config.failure.collision_shape {
+            CollisionShape::Point => vec![state.position],
+            CollisionShape::Footprint => lander_footprint(state).to_vec(),
+        };
+
+        let out_of_bounds = points.iter().any(|point| {
+            let rel_pos = *point - reference_pos;
+            rel_pos.x < bounds.x_min
+                || rel_pos.x > bounds.x_max
+                || rel_pos.y < bounds.y_min
+                || rel_pos.y > bounds.y_max
+        });
+        if out_of_bounds {
             return true;
         }
     }
 
-    false
+    // Any part of the lander inside a no-fly box
+    let footprint = lander_footprint(state);
+    config.failure.obstacles.iter().any(|obstacle| {
+        let (min, max) = obstacle.world_bounds(&config.initial);
+        footprint_overlaps_box(&footprint, min, max)
+    })
 }
 
+// Fixed physics timestep (seconds); the script's control() runs once per step
+pub const PHYSICS_DT: f32 = 1.0 / 120.0;
+// Most physics steps run in one frame. Any time beyond that is dropped so a slow
+// frame can't snowball into ever longer catch-up frames.
+const MAX_STEPS_PER_FRAME: u32 = 12;
+
+// Integration statistics for the debug overlay
+#[derive(Resource, Default)]
+pub struct SimStats {
+    pub step_dt: f32,    // Timestep of the last physics step (seconds)
+    pub substeps: u32,   // Physics steps run during the last frame
+    pub step_rate: f32,  // Physics steps per real second, smoothed
+    pub time_scale: f32, // Simulated seconds per real second during the last frame
+}
+
+// Blend factor for smoothing the displayed step rate
+const STEP_RATE_SMOOTHING: f32 = 0.1;
+
+#[allow(clippy::too_many_arguments)]
 pub fn simulation_system(
     time: Res<Time>,
     mut state: ResMut<LanderState>,
     level: Res<CurrentLevel>,
     mut script_engine: ResMut<ScriptEngine>,
+    mut trajectory: ResMut<Trajectory>,
+    mut editor_state: ResMut<EditorState>,
+    mut stats: ResMut<SimStats>,
+    mut telemetry: ResMut<TelemetryLog>,
+    manual_input: Res<ManualInput>,
 ) {
     // Only run simulation if we have a level config
     if !state.landed && !state.crashed {
-        let dt = time.delta_secs();
+        let frame_dt = time.delta_secs();
+        let mut sim_dt = frame_dt * editor_state.time_scale;
+        state.attitude_assist = editor_state.attitude_assist
+            && matches!(level.config.control_scheme, ControlScheme::ThrustVector);
+        state.manual_control = (editor_state.control_source == ControlSource::Manual)
+            .then(|| manual_input.control_output(&level.config, state.attitude_assist));
+        if state.manual_control.is_some() {
+            // Hand-flown runs don't count towards best runs
+            state.assisted = true;
+        }
+        if editor_state.precision_assist {
+            let time_scale = precision_time_scale(&state, &level.config);
+            if time_scale < 1.0 {
+                sim_dt *= time_scale;
+                state.assisted = true;
+            }
+        }
 
-        // Create control state for script
-        let script_state = ScriptLanderState {
-            x: state.position.x,
-            y: state.position.y,
-            vx: state.velocity.x,
-            vy: state.velocity.y,
-            rotation: state.rotation,
-            angular_vel: state.angular_vel,
-            fuel: state.fuel,
-        };
+        // Stepping while paused advances exactly one tick, whatever the frame time
+        if editor_state.step_requested {
+            editor_state.step_requested = false;
+            state.accumulator = 0.0;
+            sim_dt = PHYSICS_DT;
+        }
 
-        // Get thrust and gimbal commands from script
-        let mut new_thrust;
-        let mut new_gimbal;
+        // Fast-forward gets a proportionally larger step budget per frame
+        let max_steps = MAX_STEPS_PER_FRAME as f32 * editor_state.time_scale.max(1.0);
+        let substeps = advance_fixed_steps(
+            &mut state,
+            &level.config,
+            &mut script_engine,
+            &mut trajectory,
+            &mut telemetry,
+            sim_dt,
+            max_steps,
+        );
 
-        if let Some(control) = script_engine.calculate_control(script_state) {
-            match control {
-                ControlOutput::Simple(simple) => {
-                    new_thrust = simple.thrust;
-                    new_gimbal = 0.0;
-                }
-                ControlOutput::Vectored(vectored) => {
-                    new_thrust = vectored.thrust;
-                    new_gimbal = vectored.gimbal;
-                }
-            }
-        } else {
-            // Script error occurred - maintain current values
-            return;
+        stats.step_dt = PHYSICS_DT;
+        stats.substeps = substeps;
+        if frame_dt > 0.0 {
+            let rate = stats.substeps as f32 / frame_dt;
+            stats.step_rate += (rate - stats.step_rate) * STEP_RATE_SMOOTHING;
+            stats.time_scale = PHYSICS_DT * stats.substeps as f32 / frame_dt;
+        }
+    }
+}
+
+// Step the physics in fixed increments so the result doesn't depend on the frame
+// rate, carrying the leftover time over to the next frame. At most `max_steps` steps
+// worth of time is kept. Returns the number of steps taken.
+pub fn advance_fixed_steps(
+    state: &mut LanderState,
+    config: &LevelConfig,
+    script_engine: &mut ScriptEngine,
+    trajectory: &mut Trajectory,
+    telemetry: &mut TelemetryLog,
+    sim_dt: f32,
+    max_steps: f32,
+) -> u32 {
+    state.accumulator = (state.accumulator + sim_dt).min(PHYSICS_DT * max_steps);
+    let mut substeps = 0;
+    while state.accumulator >= PHYSICS_DT && !state.landed && !state.crashed {
+        let elapsed = state.elapsed;
+        step_simulation(state, config, script_engine, trajectory, PHYSICS_DT);
+        state.accumulator -= PHYSICS_DT;
+        substeps += 1;
+
+        // A script error stops time; don't spin on it for the rest of the frame
+        if state.elapsed == elapsed {
+            break;
+        }
+        telemetry.record(state);
+    }
+    substeps
+}
+
+// Advance the lander by `dt` seconds: query the script, apply the controls and
+// integrate the physics. Doesn't depend on the Bevy schedule, so headless runs use it too.
+pub fn step_simulation(
+    state: &mut LanderState,
+    config: &LevelConfig,
+    script_engine: &mut ScriptEngine,
+    trajectory: &mut Trajectory,
+    dt: f32,
+) {
+    if state.landed || state.crashed {
+        return;
+    }
+
+    // Create control state for script
+    let target = state.current_target(config);
+    let script_state = ScriptLanderState {
+        x: state.position.x,
+        y: state.position.y,
+        agl: config.height_above_ground(state.position) - LANDER_BASE_OFFSET,
+        vx: state.velocity.x,
+        vy: state.velocity.y,
+        rotation: state.rotation,
+        angular_vel: state.angular_vel,
+        fuel: state.fuel,
+        mass: state.total_mass(config),
+        wind: state.steady_wind(config),
+        g: config
+            .physics
+            .gravity_at(state.position)
+            .dot(config.physics.local_up(state.position)),
+        low_fuel: state.is_low_fuel(config),
+        engine_heat: state.heat_fraction(config),
+        gimbal_limit: config.physics.gimbal_max,
+        gimbal_rate_limit: config.physics.gimbal_rate,
+        time: state.elapsed,
+        jettisoned: state.jettisoned,
+        target_x: target.x,
+        target_y: target.y,
+        thrust_actual: state.thrust_level,
+        gimbal_actual: state.gimbal_angle,
+        engine_ready: state.engine_ready(config),
+        rcs_fuel: state.rcs_fuel,
+        landing_target: config.final_target(),
+        downrange_axis: config.downrange_axis(),
+    };
+
+    // Manual commands replace the script's output; the same limits apply in the step
+    let control = match state.manual_control.clone() {
+        Some(manual) => Some(manual),
+        None => script_engine.calculate_control(script_state),
+    };
+    let Some(control) = control else {
+        // The script aborted the run, or a script error occurred - maintain current values
+        if script_engine.abort_message.is_some() {
+            state.crashed = true;
+            trajectory.record(state);
         }
+        return;
+    };
+
+    // Drop the stage if the script asked for it: a step change in mass for this step
+    if script_engine.take_jettison_request() && !state.jettisoned {
+        if let Some(stage) = &config.physics.stage {
+            state.jettisoned = true;
+            state.fuel = (state.fuel - stage.fuel).max(0.0);
+        }
+    }
 
-        // Apply rate limits and clamps to controls
-        new_thrust = new_thrust.clamp(MIN_THRUST, MAX_THRUST);
-        new_gimbal = new_gimbal.clamp(MIN_GIMBAL_ANGLE, MAX_GIMBAL_ANGLE);
+    // Attitude thrusters fire at the commanded level straight away, while propellant lasts
+    let rcs_command = script_engine.take_rcs_command().clamp(-1.0, 1.0);
+    state.rcs_level = if config.physics.rcs_max_torque > 0.0 && state.rcs_fuel > 0.0 {
+        rcs_command
+    } else {
+        0.0
+    };
+    state.rcs_fuel =
+        (state.rcs_fuel - state.rcs_level.abs() * config.physics.rcs_fuel_rate * dt).max(0.0);
+
+    step_2d(state, config, control, dt);
+    trajectory.record(state);
+}
 
-        // Rate limit the thrust changes
-        let max_thrust_delta = MAX_THRUST_CHANGE_RATE * dt;
-        new_thrust = if new_thrust > state.thrust_level {
-            (state.thrust_level + max_thrust_delta).min(new_thrust)
+// One physics step for a given control output: actuator limits, integration, ground
+// contact and the success/failure checks. Doesn't touch the script engine or any Bevy
+// resources, so it can be driven directly with canned commands.
+pub fn step_2d(state: &mut LanderState, config: &LevelConfig, control: ControlOutput, dt: f32) {
+    if state.landed || state.crashed {
+        return;
+    }
+
+    let mut new_thrust;
+    let mut new_gimbal;
+    let mut new_lateral = 0.0;
+    match control {
+        ControlOutput::Simple(simple) => {
+            new_thrust = simple.thrust;
+            new_gimbal = 0.0;
+        }
+        ControlOutput::Vectored(vectored) if state.attitude_assist => {
+            // The second output is a sideways command; the assist tilts the lander
+            // for it and flies the gimbal itself
+            new_thrust = vectored.thrust;
+            let target_rotation = -vectored.gimbal.clamp(-1.0, 1.0) * ATTITUDE_ASSIST_MAX_TILT;
+            new_gimbal = attitude_hold_gimbal(state, config, target_rotation);
+            state.assisted = true;
+        }
+        ControlOutput::Vectored(vectored) => {
+            new_thrust = vectored.thrust;
+            new_gimbal = vectored.gimbal;
+        }
+        ControlOutput::Translate(translate) => {
+            new_thrust = translate.thrust;
+            new_gimbal = 0.0;
+            new_lateral = translate.lateral;
+        }
+    }
+
+    // Apply rate limits and clamps to controls
+    new_thrust = new_thrust.clamp(MIN_THRUST, MAX_THRUST);
+    // Not clamp(), which panics if a level slipped through with min above max
+    new_gimbal = new_gimbal
+        .max(config.physics.gimbal_min)
+        .min(config.physics.gimbal_max);
+
+    // The engine can't run below its minimum throttle: commands under it go to
+    // whichever of off and the minimum is closer
+    let min_throttle = config.physics.min_throttle;
+    if new_thrust > 0.0 && new_thrust < min_throttle {
+        new_thrust = if new_thrust >= min_throttle / 2.0 {
+            min_throttle
         } else {
-            (state.thrust_level - max_thrust_delta).max(new_thrust)
+            0.0
         };
+    }
 
-        // Rate limit the gimbal changes
-        let max_gimbal_delta = MAX_GIMBAL_RATE * dt;
-        new_gimbal = if new_gimbal > state.gimbal_angle {
-            (state.gimbal_angle + max_gimbal_delta).min(new_gimbal)
+    // After being off, the engine only produces thrust once the startup delay has passed
+    if new_thrust > 0.0 {
+        state.ignition_timer += dt;
+    } else {
+        state.ignition_timer = 0.0;
+    }
+    if !state.engine_ready(config) {
+        new_thrust = 0.0;
+    }
+
+    // Rate limit the thrust changes
+    let max_thrust_delta = config.physics.thrust_rate * dt;
+    let commanded_thrust = new_thrust;
+    new_thrust = if new_thrust > state.thrust_level {
+        (state.thrust_level + max_thrust_delta).min(new_thrust)
+    } else {
+        (state.thrust_level - max_thrust_delta).max(new_thrust)
+    };
+    // Lighting up goes straight to the minimum throttle and shutting down straight to off
+    if new_thrust < min_throttle {
+        new_thrust = if commanded_thrust > 0.0 {
+            min_throttle
         } else {
-            (state.gimbal_angle - max_gimbal_delta).max(new_gimbal)
+            0.0
         };
+    }
 
-        // Update control state
-        state.thrust_level = new_thrust;
-        state.gimbal_angle = new_gimbal;
+    // Rate limit the gimbal changes
+    let max_gimbal_delta = config.physics.gimbal_rate * dt;
+    new_gimbal = if new_gimbal > state.gimbal_angle {
+        (state.gimbal_angle + max_gimbal_delta).min(new_gimbal)
+    } else {
+        (state.gimbal_angle - max_gimbal_delta).max(new_gimbal)
+    };
+
+    // Rate limit the lateral thruster the same way as the main engine
+    new_lateral = new_lateral.clamp(-MAX_THRUST, MAX_THRUST);
+    new_lateral = if new_lateral > state.lateral_thrust {
+        (state.lateral_thrust + max_thrust_delta).min(new_lateral)
+    } else {
+        (state.lateral_thrust - max_thrust_delta).max(new_lateral)
+    };
+
+    // Update control state
+    state.thrust_level = new_thrust;
+    state.gimbal_angle = new_gimbal;
+    state.lateral_thrust = new_lateral;
 
-        // Force thrust to 0 if out of fuel
-        if state.fuel <= 0.0 {
+    // Force thrust to 0 if out of fuel
+    if state.fuel <= 0.0 {
+        state.thrust_level = 0.0;
+        state.gimbal_angle = 0.0;
+        state.lateral_thrust = 0.0;
+    }
+
+    // An overheated engine stays off until it has cooled down
+    if let Some(overheat) = &config.physics.overheat {
+        if state.engine_overheated {
+            state.thrust_level = 0.0;
+        }
+        if update_engine_heat(state, overheat, dt) {
+            state.crashed = true;
             state.thrust_level = 0.0;
             state.gimbal_angle = 0.0;
+            return;
         }
+    }
 
-        let config = &level.config;
+    // Fuel burn depends only on the throttles, which stay fixed for the whole step
+    let thrust_magnitude =
+        state.thrust_force(config).length() + state.lateral_force(config).length();
 
-        // Calculate current mass
-        let total_mass = config.physics.dry_mass + state.fuel;
+    // Where the step started, to find when during it the lander reached the ground
+    let start_clearance = ground_clearance(state, config);
+    let start_velocity = state.velocity;
 
-        // When rotation is 0 (pointing up):
-        //   - thrust should be upward
-        //   - gimbal rotates this direction
-        let thrust_direction = -state.rotation - state.gimbal_angle;
+    match config.physics.integrator {
+        Integrator::Euler => {
+            let derivatives = compute_derivatives(state, config);
 
-        let thrust_force = Vec2::new(
-            thrust_direction.sin() * state.thrust_level * config.physics.max_thrust,
-            thrust_direction.cos() * state.thrust_level * config.physics.max_thrust,
-        );
+            // Update angular velocity and rotation
+            state.angular_vel += derivatives.angular_acc * dt;
+            state.rotation += state.angular_vel * dt;
 
-        // Calculate gravity force (y-axis only)
-        let gravity_force = Vec2::new(0.0, config.physics.gravity * total_mass);
+            // Update linear velocity and position using simple Euler integration
+            state.velocity += derivatives.acceleration * dt;
+            state.position += derivatives.velocity * dt;
+        }
+        Integrator::Rk4 => integrate_rk4(state, config, dt),
+    }
+    state.rotation = state.rotation.sin().atan2(state.rotation.cos());
+    state.elapsed += dt;
 
-        // Sum forces and calculate linear acceleration
-        let total_force = thrust_force + gravity_force;
-        let acceleration = total_force / total_mass;
+    // Ground collision check - check failure first
+    let clearance = ground_clearance(state, config);
+    let sprung_legs = config.physics.leg_stiffness > 0.0;
+    state.leg_compression = if sprung_legs {
+        (-clearance).max(0.0)
+    } else {
+        0.0
+    };
+    if clearance <= 0.0 {
+        // A fast or long step can end well below the ground. Judge the contact by the
+        // velocity at the moment of crossing, interpolated through the step, so the
+        // outcome doesn't depend on the step size. Sprung legs carry on from where
+        // the step ended, as the spring force takes over from here.
+        let mut contact_velocity = state.velocity;
+        if start_clearance > 0.0 {
+            let fraction = start_clearance / (start_clearance - clearance);
+            contact_velocity = start_velocity.lerp(state.velocity, fraction);
+        }
+        if !sprung_legs {
+            state.velocity = contact_velocity;
+        }
 
-        // Calculate torque from offset thrust
-        let thrust_torque = if state.thrust_level > 0.0 {
-            -state.gimbal_angle.sin()
-                * state.thrust_level
-                * config.physics.max_thrust
-                * LANDER_BASE_OFFSET
-        } else {
-            0.0
-        };
+        // Remember how fast we hit the ground before contact zeroes the velocity
+        let first_contact = state.touchdown_velocity.is_none();
+        if first_contact {
+            state.touchdown_velocity = Some(contact_velocity);
+        }
 
-        // Add artificial angular damping
-        let damping_torque = -state.angular_vel * ANGULAR_DAMPING;
-        let total_torque = thrust_torque + damping_torque;
-
-        // Update angular velocity and rotation
-        let angular_acc = total_torque / MOMENT_OF_INERTIA;
-        state.angular_vel += angular_acc * dt;
-        state.rotation += state.angular_vel * dt;
-        state.rotation = state.rotation.sin().atan2(state.rotation.cos());
-
-        // Update linear velocity and position using simple Euler integration
-        let velocity = state.velocity;
-        state.velocity += acceleration * dt;
-        state.position += velocity * dt;
-
-        // Ground collision check - check failure first
-        if state.position.y <= LANDER_BASE_OFFSET {
-            // Check for crash before zeroing velocity
-            if check_failure_conditions(&state, &level) {
-                state.crashed = true;
-                state.position.y = LANDER_BASE_OFFSET;
-                state.velocity = Vec2::ZERO;
-                state.angular_vel = 0.0;
-                state.thrust_level = 0.0;
-                state.gimbal_angle = 0.0;
-                return;
-            }
+        // Check for crash before zeroing velocity
+        if check_failure_conditions(state, config) {
+            state.crashed = true;
+            state.position -= config.physics.local_up(state.position) * clearance;
+            state.velocity = Vec2::ZERO;
+            state.angular_vel = 0.0;
+            state.thrust_level = 0.0;
+            state.gimbal_angle = 0.0;
+            state.lateral_thrust = 0.0;
+            state.rcs_level = 0.0;
+            return;
+        }
 
+        // Standing on sprung legs: the legs push back in the next steps instead of the
+        // lander stopping dead, and they hold its attitude
+        if sprung_legs {
+            state.angular_vel = 0.0;
+            state.thrust_level = 0.0;
+            state.gimbal_angle = 0.0;
+            state.lateral_thrust = 0.0;
+            state.rcs_level = 0.0;
+        } else {
             // Not a crash, normal ground contact
-            state.position.y = LANDER_BASE_OFFSET;
+            state.position -= config.physics.local_up(state.position) * clearance;
             state.velocity = Vec2::ZERO;
             state.angular_vel = 0.0;
             state.thrust_level = 0.0;
             state.gimbal_angle = 0.0;
+            state.lateral_thrust = 0.0;
+            state.rcs_level = 0.0;
+
+            // Uneven ground kicks the lander on first contact
+            if let (true, Some(terrain)) = (first_contact, &config.rough_terrain) {
+                let bump = state.rng.0.gen_range(0.0..=terrain.max_bump_velocity);
+                let spin = state
+                    .rng
+                    .0
+                    .gen_range(-terrain.max_bump_angular_vel..=terrain.max_bump_angular_vel);
+                state.velocity = config.physics.local_up(state.position) * bump;
+                state.angular_vel = spin;
+            }
         }
+    }
 
-        // Calculate fuel consumption
-        let thrust_magnitude = thrust_force.length();
-        let fuel_flow = calculate_mass_flow(thrust_magnitude, config.physics.isp);
-        state.fuel = (state.fuel - fuel_flow * dt).max(0.0);
+    // Calculate fuel consumption
+    let fuel_flow = calculate_mass_flow(thrust_magnitude, config.physics.isp);
+    state.fuel = (state.fuel - fuel_flow * dt).max(0.0);
 
-        // Check success/failure conditions
-        if check_failure_conditions(&state, &level) {
-            state.crashed = true;
-            return;
-        }
+    advance_waypoints(state, config);
 
-        // Check for success conditions
-        if check_success_conditions(&state, &level) {
-            state.success_timer += dt;
-            state.stabilizing = true;
+    // Check success/failure conditions
+    if check_failure_conditions(state, config) {
+        state.crashed = true;
+        return;
+    }
 
-            // Check if we've met the persistence requirement
-            if state.success_timer >= config.success.persistence_period {
-                state.landed = true;
-                state.stabilizing = false;
-            }
-        } else {
-            // Reset the timer if any condition is not met
-            state.success_timer = 0.0;
+    // Check for success conditions
+    state.success_checks = check_success_conditions(state, config);
+    if state.success_checks.all() {
+        state.success_timer += dt;
+        state.stabilizing = true;
+
+        // Check if we've met the persistence requirement
+        if state.success_timer >= config.success.persistence_period {
+            state.landed = true;
             state.stabilizing = false;
         }
+    } else {
+        // Reset the timer if any condition is not met
+        state.success_timer = 0.0;
+        state.stabilizing = false;
     }
 }
 
+// Largest tilt from upright the attitude assist commands (radians)
+const ATTITUDE_ASSIST_MAX_TILT: f32 = 0.3;
+// Attitude assist loop gains: angular acceleration per radian of error and per rad/s
+const ATTITUDE_ASSIST_KP: f32 = 4.0;
+const ATTITUDE_ASSIST_KD: f32 = 4.0;
+
+// Gimbal angle that drives the lander's attitude towards the target with a PD loop.
+// Needs the engine running, since the gimbal only makes torque with thrust.
+fn attitude_hold_gimbal(state: &LanderState, config: &LevelConfig, target_rotation: f32) -> f32 {
+    let torque_per_sin =
+        state.thrust_level * config.physics.max_thrust * state.thrust_moment_arm(config);
+    if torque_per_sin <= f32::EPSILON {
+        return 0.0;
+    }
+
+    let error = target_rotation - state.rotation;
+    let angular_acc = ATTITUDE_ASSIST_KP * error - ATTITUDE_ASSIST_KD * state.angular_vel;
+    // Thrust torque is -sin(gimbal) * thrust * moment arm, see compute_derivatives
+    let sin_gimbal = -(angular_acc * state.moment_of_inertia(config)) / torque_per_sin;
+    sin_gimbal.clamp(-1.0, 1.0).asin()
+}
+
+// Time derivatives of the integrated part of the lander state
+struct Derivatives {
+    velocity: Vec2,     // d(position)/dt
+    acceleration: Vec2, // d(velocity)/dt
+    angular_vel: f32,   // d(rotation)/dt
+    angular_acc: f32,   // d(angular_vel)/dt
+}
+
+// Forces and torques for the current controls, evaluated at the state's position,
+// velocity and attitude. Mass is taken as constant over a step.
+fn compute_derivatives(state: &LanderState, config: &LevelConfig) -> Derivatives {
+    let total_mass = state.total_mass(config);
+
+    let thrust_force = state.thrust_force(config);
+    let lateral_force = state.lateral_force(config);
+    let gravity_force = state.gravity_force(config);
+    let wind_force = state.wind_force(config);
+    let drag_force = state.drag_force(config);
+    let leg_force = state.leg_force(config);
+
+    // Sum forces and calculate linear acceleration. The lateral thruster, the wind,
+    // drag and the legs act through the center of mass, so they add no torque.
+    let total_force =
+        thrust_force + lateral_force + gravity_force + wind_force + drag_force + leg_force;
+
+    // Calculate torque from offset thrust
+    let thrust_torque = if state.thrust_level > 0.0 {
+        -state.gimbal_angle.sin()
+            * state.thrust_level
+            * config.physics.max_thrust
+            * state.thrust_moment_arm(config)
+    } else {
+        0.0
+    };
+
+    // Attitude thrusters fire in balanced pairs, so they add torque but no net force
+    let rcs_torque = state.rcs_level * config.physics.rcs_max_torque;
+
+    // Add artificial angular damping
+    let damping_torque = -state.angular_vel * ANGULAR_DAMPING;
+    let total_torque = thrust_torque + rcs_torque + damping_torque;
+
+    Derivatives {
+        velocity: state.velocity,
+        acceleration: total_force / total_mass,
+        angular_vel: state.angular_vel,
+        angular_acc: total_torque / state.moment_of_inertia(config),
+    }
+}
+
+// Classic fourth-order Runge-Kutta step with the controls held for the whole step
+fn integrate_rk4(state: &mut LanderState, config: &LevelConfig, dt: f32) {
+    // Lander state advanced from the start of the step along a derivative estimate
+    let stage = |derivatives: &Derivatives, h: f32| {
+        let mut stage = state.clone();
+        stage.position += derivatives.velocity * h;
+        stage.velocity += derivatives.acceleration * h;
+        stage.rotation += derivatives.angular_vel * h;
+        stage.angular_vel += derivatives.angular_acc * h;
+        stage.elapsed += h;
+        stage
+    };
+
+    let k1 = compute_derivatives(state, config);
+    let k2 = compute_derivatives(&stage(&k1, dt / 2.0), config);
+    let k3 = compute_derivatives(&stage(&k2, dt / 2.0), config);
+    let k4 = compute_derivatives(&stage(&k3, dt), config);
+
+    let weighted = |a: Vec2, b: Vec2, c: Vec2, d: Vec2| (a + 2.0 * b + 2.0 * c + d) / 6.0;
+    let weighted_f32 = |a: f32, b: f32, c: f32, d: f32| (a + 2.0 * b + 2.0 * c + d) / 6.0;
+
+    state.position += weighted(k1.velocity, k2.velocity, k3.velocity, k4.velocity) * dt;
+    state.velocity += weighted(
+        k1.acceleration,
+        k2.acceleration,
+        k3.acceleration,
+        k4.acceleration,
+    ) * dt;
+    state.rotation += weighted_f32(
+        k1.angular_vel,
+        k2.angular_vel,
+        k3.angular_vel,
+        k4.angular_vel,
+    ) * dt;
+    state.angular_vel += weighted_f32(
+        k1.angular_acc,
+        k2.angular_acc,
+        k3.angular_acc,
+        k4.angular_acc,
+    ) * dt;
+}
+
 pub fn reset_simulation(
     state: &mut LanderState,
     level: &CurrentLevel,
     camera_state: &mut CameraState,
+    trajectory: &mut Trajectory,
+    script_engine: &mut ScriptEngine,
 ) {
+    reset_lander(state, &level.config, trajectory);
+    script_engine.reset_runtime_state();
+
+    // Reset camera to following state
+    camera_state.following = true;
+    camera_state.target_offset.x = 0.0;
+    camera_state.target_offset.y = 0.0;
+    camera_state.lead = Vec2::ZERO;
+    camera_state.explosion_spawned = false;
+}
+
+// Put the lander back at the level's initial conditions and restart the recording
+pub fn reset_lander(state: &mut LanderState, config: &LevelConfig, trajectory: &mut Trajectory) {
+    let initial = &config.initial;
+    // Keep the seed so that a reset repeats the same run
+    let seed = state.seed;
     *state = LanderState {
-        position: Vec2::new(level.config.initial.x0, level.config.initial.y0),
-        velocity: Vec2::new(level.config.initial.vx0, level.config.initial.vy0),
-        rotation: level.config.initial.initial_angle,
-        angular_vel: 0.0,
-        fuel: level.config.initial.initial_fuel,
-        thrust_level: 0.0,
+        position: Vec2::new(initial.x0, initial.y0),
+        velocity: Vec2::new(initial.vx0, initial.vy0),
+        rotation: initial.initial_angle,
+        angular_vel: initial.initial_angular_vel,
+        fuel: initial.initial_fuel,
+        thrust_level: initial.initial_thrust.clamp(MIN_THRUST, MAX_THRUST),
         gimbal_angle: 0.0,
+        lateral_thrust: 0.0,
         crashed: false,
         landed: false,
         success_timer: 0.0,
         stabilizing: false,
+        elapsed: 0.0,
+        engine_heat: 0.0,
+        engine_overheated: false,
+        touchdown_velocity: None,
+        assisted: false,
+        seed,
+        accumulator: 0.0,
+        attitude_assist: state.attitude_assist,
+        dispersion: state.dispersion,
+        jettisoned: false,
+        success_checks: SuccessChecks::default(),
+        manual_control: None,
+        waypoint_index: 0,
+        // An engine that starts the run lit is already past its startup delay
+        ignition_timer: if initial.initial_thrust > 0.0 {
+            config.physics.startup_delay
+        } else {
+            0.0
+        },
+        rcs_fuel: config.physics.rcs_fuel,
+        rcs_level: 0.0,
+        leg_compression: 0.0,
+        rng: SimRng::from_seed(seed),
     };
 
-    // Reset camera to following state
-    camera_state.following = true;
-    camera_state.target_offset.x = 0.0;
-    camera_state.target_offset.y = 0.0;
-    camera_state.explosion_spawned = false;
+    // Spread the initial state by the level's dispersion, drawn from the run seed
+    if let Some(dispersion) = config.dispersion.as_ref().filter(|_| state.dispersion) {
+        let mut rng = StdRng::seed_from_u64(seed ^ dispersion.seed ^ DISPERSION_STREAM);
+        state.position.x += dispersion.x0 * standard_normal(&mut rng);
+        state.velocity.x += dispersion.vx0 * standard_normal(&mut rng);
+        state.velocity.y += dispersion.vy0 * standard_normal(&mut rng);
+        state.rotation += dispersion.initial_angle * standard_normal(&mut rng);
+    }
+
+    // Start a fresh recording from the initial state
+    trajectory.samples.clear();
+    trajectory.attempt_saved = false;
+    trajectory.record(state);
+}
+
+// Gaussian sample with zero mean and unit variance (Box-Muller)
+fn standard_normal(rng: &mut StdRng) -> f32 {
+    let u1: f32 = rng.gen_range(f32::EPSILON..1.0);
+    let u2: f32 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+}
+
+// How the lander came down on a successful run, for the level-complete popup
+#[derive(Resource, Default, Clone)]
+pub struct TouchdownReport {
+    pub vertical_speed: f32,   // at first ground contact (m/s)
+    pub horizontal_speed: f32, // at first ground contact (m/s)
+    pub tilt: f32,             // away from the required final angle (radians)
+    pub offset: f32,           // horizontal distance from the middle of the target (m)
+    pub fuel_remaining: f32,   // kg
+}
+
+impl TouchdownReport {
+    // Contact zeroes the velocity, so the speeds come from the moment of first contact
+    // (or the current velocity for runs that never touched down, like ascents). Both the
+    // speeds and the tilt are against the local ground, as the success check judges them.
+    pub fn capture(state: &LanderState, config: &LevelConfig) -> Self {
+        let velocity = state.touchdown_velocity.unwrap_or(state.velocity);
+        let (lateral, vertical) = state.local_components(config, velocity);
+        Self {
+            vertical_speed: vertical.abs(),
+            horizontal_speed: lateral.abs(),
+            tilt: state.attitude_error(config).abs(),
+            offset: (state.position.x - config.final_target().x).abs(),
+            fuel_remaining: state.fuel,
+        }
+    }
+}
+
+// Maximum number of points kept in a score's trajectory thumbnail
+pub const SCORE_TRAJECTORY_POINTS: usize = 100;
+// Time between the saved states of a score's ghost replay (seconds)
+pub const GHOST_SAMPLE_INTERVAL: f32 = 0.1;
+
+// Summarize a completed run for the level's best-score record
+pub fn score_run(state: &LanderState, config: &LevelConfig, trajectory: &Trajectory) -> LevelScore {
+    let target = match (
+        &config.success.objective,
+        &config.success.position_box.reference,
+    ) {
+        (
+            Objective::Ascent {
+                target_altitude, ..
+            },
+            _,
+        ) => Vec2::new(state.position.x, *target_altitude),
+        (Objective::Landing, Reference::Absolute) => Vec2::new(
+            (config.success.position_box.x_min + config.success.position_box.x_max) / 2.0,
+            state.position.y,
+        ),
+        (Objective::Landing, Reference::Initial) => Vec2::new(config.initial.x0, config.initial.y0),
+    };
+
+    LevelScore {
+        fuel_remaining: state.fuel,
+        time: state.elapsed,
+        touchdown_speed: state.touchdown_velocity.unwrap_or(state.velocity).length(),
+        accuracy: state.position.distance(target),
+        trajectory: trajectory.downsampled_path(SCORE_TRAJECTORY_POINTS),
+        ghost: trajectory.ghost_samples(GHOST_SAMPLE_INTERVAL),
+    }
+}
+
+// Stars for a finished run: none for a failure, one for passing, plus one each for
+// finishing with at least a quarter and at least half of the starting fuel
+pub fn star_rating(passed: bool, score: &LevelScore, config: &LevelConfig) -> u8 {
+    if !passed {
+        return 0;
+    }
+    let fuel_fraction = score.fuel_remaining / config.initial.initial_fuel.max(f32::EPSILON);
+    1 + (fuel_fraction >= 0.25) as u8 + (fuel_fraction >= 0.5) as u8
 }
 
 // Helper function to calculate mass flow rate based on thrust
 fn calculate_mass_flow(thrust: f32, isp: f32) -> f32 {
     thrust / (isp * 9.81) // 9.81 is standard gravity for Isp calculations
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rhai_api::SimpleControl;
+
+    // Lunar lander high above flat ground, with nothing to hit or leave
+    const TEST_LEVEL: &str = r#"(
+        name: "Test",
+        description: "",
+        hint: "",
+        physics: (gravity: -1.62, dry_mass: 50.0, max_thrust: 200.0, isp: 300.0),
+        initial: (x0: 0.0, y0: 100.0, vx0: 0.0, vy0: 0.0, initial_angle: 0.0, initial_fuel: 10.0),
+        success: (
+            vx_max: 1.0,
+            vy_max: 2.0,
+            position_box: (x_min: -10.0, x_max: 10.0, y_min: 0.0, y_max: 2.0, reference: Absolute),
+            final_angle: 0.0,
+            angle_tolerance: 0.1,
+            persistence_period: 1.0,
+        ),
+        failure: (ground_collision: false, bounds: None),
+        control_scheme: VerticalOnly,
+        success_message: "",
+        failure_message: "",
+    )"#;
+
+    fn test_level() -> LevelConfig {
+        ron::from_str(TEST_LEVEL).unwrap()
+    }
+
+    fn initial_state(config: &LevelConfig) -> LanderState {
+        let mut state = LanderState::default();
+        reset_lander(&mut state, config, &mut Trajectory::default());
+        state
+    }
+
+    fn throttle(thrust: f32) -> ControlOutput {
+        ControlOutput::Simple(SimpleControl { thrust })
+    }
+
+    // Step with the same control for `duration` seconds
+    fn fly(state: &mut LanderState, config: &LevelConfig, control: ControlOutput, duration: f32) {
+        let steps = (duration / PHYSICS_DT).round() as usize;
+        for _ in 0..steps {
+            step_2d(state, config, control.clone(), PHYSICS_DT);
+        }
+    }
+
+    #[test]
+    fn full_throttle_climbs_and_burns_fuel() {
+        let config = test_level();
+        let mut state = initial_state(&config);
+        fly(&mut state, &config, throttle(1.0), 2.0);
+
+        assert_eq!(state.thrust_level, 1.0);
+        assert!(state.velocity.y > 0.0);
+        assert!(state.position.y > config.initial.y0);
+        assert!(state.fuel < config.initial.initial_fuel);
+    }
+
+    #[test]
+    fn closed_throttle_burns_no_fuel() {
+        let config = test_level();
+        let mut state = initial_state(&config);
+        fly(&mut state, &config, throttle(0.0), 2.0);
+
+        assert_eq!(state.fuel, config.initial.initial_fuel);
+        assert!(state.velocity.y < 0.0);
+        assert!(!state.crashed && !state.landed);
+    }
+
+    #[test]
+    fn inverted_gimbal_limits_fail_validation_but_do_not_panic() {
+        let mut config = test_level();
+        config.physics.gimbal_min = 0.3;
+        config.physics.gimbal_max = -0.3;
+        assert!(config.validate().is_err());
+
+        let mut state = initial_state(&config);
+        fly(&mut state, &config, throttle(0.5), 1.0);
+        assert!(state.position.y.is_finite());
+    }
+
+    #[test]
+    fn rk4_free_fall_matches_closed_form() {
+        let mut config = test_level();
+        config.physics.integrator = Integrator::Rk4;
+        let mut state = initial_state(&config);
+        let duration = 5.0;
+        fly(&mut state, &config, throttle(0.0), duration);
+
+        let g = -config.physics.gravity;
+        let expected = config.initial.y0 - 0.5 * g * duration * duration;
+        assert!(
+            (state.position.y - expected).abs() < 0.01,
+            "y = {}, expected {}",
+            state.position.y,
+            expected
+        );
+    }
+
+    // Fly a scripted descent on frames of `frame_dt`, fed through the accumulator like
+    // the interactive simulation, and return the path one sample per physics step
+    fn fly_frames(config: &LevelConfig, script: &str, frame_dt: f32, duration: f32) -> Trajectory {
+        let mut script_engine = ScriptEngine::default();
+        script_engine.set_level(config);
+        script_engine.compile_script(script).unwrap();
+        assert!(script_engine.activate_script());
+
+        let mut state = LanderState::default();
+        let mut trajectory = Trajectory::default();
+        let mut telemetry = TelemetryLog::default();
+        reset_lander(&mut state, config, &mut trajectory);
+        let frames = (duration / frame_dt).ceil() as usize;
+        for _ in 0..frames {
+            advance_fixed_steps(
+                &mut state,
+                config,
+                &mut script_engine,
+                &mut trajectory,
+                &mut telemetry,
+                frame_dt,
+                MAX_STEPS_PER_FRAME as f32,
+            );
+        }
+        trajectory
+    }
+
+    #[test]
+    fn frame_rate_does_not_change_the_run() {
+        let config = test_level();
+        let script = "fn control(state) { 0.5 - 0.2 * (state[\"vy\"] + 2.0) }";
+        let duration = 10.0;
+        let slow = fly_frames(&config, script, 1.0 / 30.0, duration + 0.1);
+        let fast = fly_frames(&config, script, 1.0 / 144.0, duration + 0.1);
+
+        // Compare after the same number of physics steps; the frame boundaries may
+        // leave one run a step ahead of the other
+        let steps = (duration / PHYSICS_DT).round() as usize;
+        let slow_end = slow.samples[steps].position;
+        let fast_end = fast.samples[steps].position;
+        assert_eq!(slow.samples[steps].t, fast.samples[steps].t);
+        assert!(
+            slow_end.distance(fast_end) < 0.001,
+            "{:?} vs {:?}",
+            slow_end,
+            fast_end
+        );
+    }
+
+    #[test]
+    fn inertia_falls_as_fuel_burns() {
+        let config = test_level();
+        let mut state = initial_state(&config);
+        let mut previous = f32::INFINITY;
+        for i in 0..=10 {
+            state.fuel = config.initial.initial_fuel * (1.0 - i as f32 / 10.0);
+            let inertia = state.moment_of_inertia(&config);
+            assert!(
+                inertia < previous,
+                "fuel {}: {} >= {}",
+                state.fuel,
+                inertia,
+                previous
+            );
+            previous = inertia;
+        }
+    }
+
+    // Unpowered drop from just above the ground at close to the hard-landing limit
+    // (1.5 × vy_max = 3 m/s), stepped until first contact
+    fn drop_to_ground(dt: f32) -> LanderState {
+        let mut config = test_level();
+        config.initial.y0 = LANDER_BASE_OFFSET + 0.2;
+        config.initial.vy0 = -2.8;
+        let mut state = initial_state(&config);
+        while state.touchdown_velocity.is_none() {
+            step_2d(&mut state, &config, throttle(0.0), dt);
+        }
+        state
+    }
+
+    #[test]
+    fn long_step_judges_contact_at_the_crossing() {
+        // One half-second Euler step takes the lander 1.2 m below the ground; by the end
+        // of it the lander would be over the limit at 3.61 m/s
+        let dt = 0.5;
+        let config = test_level();
+        let (clearance, v0) = (0.2, -2.8);
+        let v_end = v0 + config.physics.gravity * dt;
+        let clearance_end = clearance + v0 * dt;
+        let fraction = clearance / (clearance - clearance_end);
+        let crossing = v0 + (v_end - v0) * fraction;
+
+        let coarse = drop_to_ground(dt);
+        let touchdown = coarse.touchdown_velocity.unwrap();
+        assert!(
+            (touchdown.y - crossing).abs() < 1e-4,
+            "{} vs {}",
+            touchdown.y,
+            crossing
+        );
+        assert!(touchdown.y.abs() < 3.0);
+
+        let fine = drop_to_ground(PHYSICS_DT);
+        assert_eq!(coarse.crashed, fine.crashed);
+        assert!(!coarse.crashed);
+    }
+
+    // Lander standing still in the landing box after touching down at `touchdown`
+    fn landed_after(touchdown: Vec2) -> (LanderState, LevelConfig) {
+        let mut config = test_level();
+        config.success.max_velocity_angle = Some(0.2);
+        let mut state = initial_state(&config);
+        state.position = Vec2::new(0.0, LANDER_BASE_OFFSET);
+        state.velocity = Vec2::ZERO;
+        state.touchdown_velocity = Some(touchdown);
+        (state, config)
+    }
+
+    #[test]
+    fn vertical_touchdown_passes_descent_check() {
+        let (state, config) = landed_after(Vec2::new(0.0, -1.5));
+        let checks = check_success_conditions(&state, &config);
+        assert!(checks.descent);
+        assert!(checks.all());
+    }
+
+    #[test]
+    fn drifting_touchdown_fails_descent_check() {
+        // 45° from straight down, well over the 0.2 rad limit
+        let (state, config) = landed_after(Vec2::new(1.0, -1.0));
+        let checks = check_success_conditions(&state, &config);
+        assert!(!checks.descent);
+        assert!(!checks.all());
+    }
+
+    #[test]
+    fn planet_mode_judges_speed_and_attitude_locally() {
+        // Upright and settling slowly on the surface, a long way around the planet
+        let mut config = test_level();
+        let radius = 1000.0;
+        config.physics.planet_radius = Some(radius);
+        let angle: f32 = 0.5;
+        let up = Vec2::new(angle.sin(), angle.cos());
+        let mut state = initial_state(&config);
+        state.position = Vec2::new(0.0, -radius) + up * (radius + LANDER_BASE_OFFSET);
+        state.velocity = -up;
+        state.rotation = -angle;
+        // Came straight down the local vertical
+        config.success.max_velocity_angle = Some(0.1);
+        state.touchdown_velocity = Some(-up * 1.5);
+
+        let checks = check_success_conditions(&state, &config);
+        assert!(checks.horizontal_speed);
+        assert!(checks.vertical_speed);
+        assert!(checks.attitude);
+        assert!(checks.descent);
+    }
+
+    #[test]
+    fn touchdown_report_matches_success_check() {
+        // A full spin past the final angle, touching down along the local vertical
+        let mut config = test_level();
+        let radius = 1000.0;
+        config.physics.planet_radius = Some(radius);
+        let angle: f32 = 0.5;
+        let up = Vec2::new(angle.sin(), angle.cos());
+        let mut state = initial_state(&config);
+        state.position = Vec2::new(0.0, -radius) + up * (radius + LANDER_BASE_OFFSET);
+        state.rotation = -angle + std::f32::consts::TAU + 0.05;
+        state.touchdown_velocity = Some(-up * 1.5);
+
+        let report = TouchdownReport::capture(&state, &config);
+        assert!((report.vertical_speed - 1.5).abs() < 1e-4);
+        assert!(report.horizontal_speed < 1e-4);
+        assert!((report.tilt - 0.05).abs() < 1e-4, "tilt = {}", report.tilt);
+        assert!(check_success_conditions(&state, &config).attitude);
+    }
+}
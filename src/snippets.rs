@@ -0,0 +1,47 @@
+// Code templates for the editor's Snippets menu: (menu title, code inserted at the caret).
+// The code is indented for the body of `control(state)`.
+pub const SNIPPETS: &[(&str, &str)] = &[
+    (
+        "Run once at the start",
+        r#"    // Runs on the first tick of every run
+    if !("initialized" in user_state) {
+        user_state.initialized = true;
+        user_state.start_x = state["x"];
+        user_state.start_y = state["y"];
+    }
+"#,
+    ),
+    (
+        "PID controller",
+        r#"    // PID on vertical speed, state kept in user_state between ticks
+    let kp = 1.0; // tune these
+    let ki = 0.1;
+    let kd = 0.0;
+    let target_vy = -1.0;
+    if !("pid_last_t" in user_state) {
+        user_state.pid_last_t = state["t"];
+        user_state.pid_integral = 0.0;
+        user_state.pid_last_error = 0.0;
+    }
+    let dt = state["t"] - user_state.pid_last_t;
+    let error = target_vy - state["vy"];
+    user_state.pid_integral += error * dt;
+    let derivative = if dt > 0.0 { (error - user_state.pid_last_error) / dt } else { 0.0 };
+    user_state.pid_last_error = error;
+    user_state.pid_last_t = state["t"];
+    let pid_output = kp * error + ki * user_state.pid_integral + kd * derivative;
+"#,
+    ),
+    (
+        "Clamp a value",
+        r#"    // Keep a command inside its limits (0 to 1 for the throttle)
+    let command = 0.5; // replace with your controller output
+    let command = if command > 1.0 { 1.0 } else if command < 0.0 { 0.0 } else { command };
+"#,
+    ),
+    (
+        "Print to the console",
+        r#"    console("y = " + state["y"] + " m, vy = " + state["vy"] + " m/s");
+"#,
+    ),
+];
@@ -1,22 +1,51 @@
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 use bevy_egui::egui::Hyperlink;
 use bevy_egui::{egui, EguiContexts};
 use bevy_persistent::prelude::*;
 use egui_extras::syntax_highlighting;
+use rhai::Dynamic;
+use std::collections::VecDeque;
 
 use crate::assets::ScriptAsset;
-use crate::levels::{ControlScheme, CurrentLevel, LevelManager};
-use crate::persistence::{self, LevelProgress};
-use crate::rhai_api::{ControlType, ScriptEngine};
-use crate::simulation::{reset_simulation, LanderState};
-use crate::visualization::{CameraState, ResetVisibilityFlag, ResetVisualization};
+use crate::audio::AudioSettings;
+use crate::challenge::{self, Challenge};
+use crate::constants::LANDER_BASE_OFFSET;
+use crate::level_editor::LevelEditorState;
+use crate::levels::{
+    self, ControlScheme, CurrentLevel, LevelConfig, LevelManager, Objective, Reference,
+};
+use crate::manual::{self, ControlSource};
+use crate::monte_carlo::{MonteCarlo, TRIAL_COUNT};
+use crate::persistence::{self, AngleUnit, AttemptHistory, LevelProgress, UnitSystem};
+use crate::rhai_api::{ScriptEngine, ScriptError, CONSOLE_LINE_LIMIT};
+use crate::simulation::{
+    precision_time_scale, reset_simulation, LanderState, SimStats, TelemetryLog, TelemetrySample,
+    TouchdownReport, Trajectory, TrajectoryPlayback,
+};
+use crate::snippets;
+use crate::visualization::{
+    CameraState, HeatmapDirty, ResetVisibilityFlag, ResetVisualization, ViewOptions,
+    RIGHT_PANEL_WIDTH, WORLD_TO_SCREEN_SCALE,
+};
 
 const CONSOLE_HEIGHT: f32 = 500.0;
+// Fraction of the script length limit at which the editor starts warning
+const SOURCE_LENGTH_WARNING: f32 = 0.8;
+// Seconds of no typing before the editor contents are recompiled
+const COMPILE_DEBOUNCE: f64 = 0.4;
+// Background of the editor line a script error points at
+const ERROR_LINE_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(80, 0, 0, 80);
+// Simulation speeds offered next to the run controls
+const TIME_SCALES: [f32; 4] = [0.25, 1.0, 2.0, 4.0];
+// Length of the HUD wind arrow at the level's strongest wind (pixels)
+const WIND_ARROW_LENGTH: f32 = 30.0;
 
 #[derive(Default, PartialEq)]
 pub enum SimulationState {
     #[default]
     Stopped,
+    Countdown, // Run was pressed; physics frozen until the countdown ends
     Running,
     Paused,
 }
@@ -24,22 +53,73 @@ pub enum SimulationState {
 #[derive(Resource)]
 pub struct EditorState {
     pub code: String,
+    pub notes: String,                 // Free-form notes for the current level
+    pub renaming_slot: Option<String>, // New name being typed for the active solution slot
+    pub seed_text: String,             // Run seed as typed in Settings, applied once it parses
     pub simulation_state: SimulationState,
     pub console_height: f32,
-    pub last_console_output: Vec<String>,
+    pub console_lines: VecDeque<String>, // Script output of the run, oldest first
+    pub console_autoscroll: bool,        // Keep the console scrolled to the newest line
     pub show_reset_confirmation: bool,
     pub default_script_handle: Handle<ScriptAsset>,
+    pub precision_assist: bool, // Slow the simulation down close to the success envelope
+    pub countdown_duration: f32, // Seconds of "3-2-1" before a run starts (0 = off)
+    pub countdown_remaining: f32,
+    pub auto_pause_offscreen: bool, // Pause when the lander flies out of view of the target
+    pub offscreen_paused: bool,     // The current pause was triggered by the off-screen check
+    pub attitude_assist: bool,      // Hold the attitude for the script on vectored levels
+    pub cursor: Option<usize>,      // Caret position in the code editor (characters)
+    pub pending_cursor: Option<usize>, // Caret to put back after the code was edited from outside
+    pub code_edited_at: Option<f64>, // egui time of the last keystroke not compiled yet
+    pub scroll_to_cursor: bool,     // Bring the pending caret into view, e.g. an error line
+    pub control_source: ControlSource, // Script or hand-flown
+    pub time_scale: f32,            // Simulated seconds per real second
+    pub step_requested: bool,       // Advance one physics tick while paused
+    pub units: UnitSystem,          // Display units for the telemetry and HUD
+    pub angle_units: AngleUnit,
 }
 
 impl Default for EditorState {
     fn default() -> Self {
         Self {
             code: include_str!("../assets/scripts/level0_default.rhai").into(),
+            notes: String::new(),
+            renaming_slot: None,
+            seed_text: String::new(),
             simulation_state: SimulationState::Stopped,
             console_height: 150.0,
-            last_console_output: Vec::new(),
+            console_lines: VecDeque::new(),
+            console_autoscroll: true,
             show_reset_confirmation: false,
             default_script_handle: Handle::default(),
+            precision_assist: false,
+            countdown_duration: 0.0,
+            countdown_remaining: 0.0,
+            auto_pause_offscreen: false,
+            offscreen_paused: false,
+            attitude_assist: false,
+            cursor: None,
+            pending_cursor: None,
+            code_edited_at: None,
+            scroll_to_cursor: false,
+            control_source: ControlSource::Script,
+            time_scale: 1.0,
+            units: UnitSystem::Metric,
+            angle_units: AngleUnit::Degrees,
+            step_requested: false,
+        }
+    }
+}
+
+impl EditorState {
+    // Start a freshly reset run, going through the countdown if one is configured
+    pub fn start_run(&mut self) {
+        self.offscreen_paused = false;
+        if self.countdown_duration > 0.0 {
+            self.countdown_remaining = self.countdown_duration;
+            self.simulation_state = SimulationState::Countdown;
+        } else {
+            self.simulation_state = SimulationState::Running;
         }
     }
 }
@@ -54,27 +134,69 @@ pub struct HintPopupState {
     pub show: bool,
 }
 
+#[derive(Resource, Default)]
+pub struct ChallengePopupState {
+    pub show: bool,
+    pub code: String,
+    pub message: Option<String>, // Result of the last generate or load
+}
+
+#[derive(Resource)]
+pub struct StatsPopupState {
+    pub show: bool,
+    pub left_level: usize,
+    pub right_level: usize,
+}
+
+impl Default for StatsPopupState {
+    fn default() -> Self {
+        Self {
+            show: false,
+            left_level: 0,
+            right_level: 1,
+        }
+    }
+}
+
 // Native-only imports
 #[cfg(not(target_arch = "wasm32"))]
 use rfd::FileDialog;
 
+#[allow(clippy::too_many_arguments)]
 pub fn ui_system(
     mut contexts: EguiContexts,
     mut editor_state: ResMut<EditorState>,
     mut script_engine: ResMut<ScriptEngine>,
     mut lander_state: ResMut<LanderState>,
     mut current_level: ResMut<CurrentLevel>,
-    mut camera_state: ResMut<CameraState>,
+    (mut camera_state, mut view_options, mut heatmap_dirty, mut audio_settings): (
+        ResMut<CameraState>,
+        ResMut<ViewOptions>,
+        ResMut<HeatmapDirty>,
+        ResMut<AudioSettings>,
+    ),
     mut reset_flag: ResMut<ResetVisibilityFlag>,
     mut reset_vis: ResMut<ResetVisualization>,
     level_manager: Res<LevelManager>,
     mut state: ResMut<NextState<GameState>>,
-    progress: ResMut<Persistent<LevelProgress>>,
+    (mut progress, mut history): (
+        ResMut<Persistent<LevelProgress>>,
+        ResMut<Persistent<AttemptHistory>>,
+    ),
     mut popup: ResMut<LevelCompletePopup>,
-    mut about_popup: ResMut<AboutPopupState>,
-    mut hint_popup: ResMut<HintPopupState>,
+    (mut about_popup, mut hint_popup, mut challenge_popup): (
+        ResMut<AboutPopupState>,
+        ResMut<HintPopupState>,
+        ResMut<ChallengePopupState>,
+    ),
     asset_server: Res<AssetServer>,
     script_assets: Res<Assets<ScriptAsset>>,
+    (mut trajectory, mut playback, telemetry, mut monte_carlo): (
+        ResMut<Trajectory>,
+        ResMut<TrajectoryPlayback>,
+        Res<TelemetryLog>,
+        ResMut<MonteCarlo>,
+    ),
 ) {
     let new_level_number = None;
     let mut reset_requested = false;
@@ -88,22 +210,199 @@ pub fn ui_system(
                     .iter()
                     .find(|(_, name)| name == &current_level.config.name)
                 {
-                    let script_handle =
-                        asset_server.load(format!("scripts/level{}_default.rhai", level_num));
+                    let script_handle = asset_server
+                        .load(level_manager.default_script_path(*level_num, &current_level.config));
                     editor_state.default_script_handle = script_handle;
 
                     let _ = persistence::save_editor_state(
                         *level_num,
                         editor_state.code.clone(),
-                        progress,
+                        editor_state.notes.clone(),
+                        &mut progress,
                     );
                 }
                 popup.show = false;
                 state.set(GameState::LevelSelect);
             }
+            ui.menu_button("Settings", |ui| {
+                ui.checkbox(&mut editor_state.precision_assist, "Precision assist")
+                    .on_hover_text(
+                        "Slow the simulation down as the lander approaches the success \
+                         conditions. Assisted runs are not recorded as best runs.",
+                    );
+                ui.checkbox(&mut editor_state.attitude_assist, "Attitude assist")
+                    .on_hover_text(
+                        "On vectored levels, keep the lander upright for you: return \
+                         [thrust, sideways] instead of [thrust, gimbal]. Assisted runs are not \
+                         recorded as best runs.",
+                    );
+                if current_level.config.dispersion.is_some() {
+                    // Either change restarts the run from the new initial state
+                    if ui
+                        .checkbox(&mut lander_state.dispersion, "Dispersion")
+                        .on_hover_text(
+                            "Randomly perturb the initial position, velocity and angle, drawn \
+                             from the run seed",
+                        )
+                        .changed()
+                    {
+                        reset_requested = true;
+                    }
+                }
+                // Dispersion, turbulence and rough ground all draw from the run seed, and a
+                // reset keeps it, so typing in a seed repeats a run exactly
+                ui.horizontal(|ui| {
+                    // Edited as text: a DragValue works in f64, which can't hold every
+                    // u64 seed exactly
+                    ui.label("Seed");
+                    let response = ui
+                        .add(
+                            egui::TextEdit::singleline(&mut editor_state.seed_text)
+                                .desired_width(170.0),
+                        )
+                        .on_hover_text("Random elements of the run start from this seed");
+                    // Applied on Enter or leaving the field, so typing doesn't reset
+                    // the run at every keystroke
+                    if response.lost_focus() {
+                        match editor_state.seed_text.trim().parse() {
+                            Ok(seed) if seed != lander_state.seed => {
+                                lander_state.seed = seed;
+                                reset_requested = true;
+                            }
+                            _ => {}
+                        }
+                    }
+                    if !response.has_focus() {
+                        editor_state.seed_text = lander_state.seed.to_string();
+                    }
+                    if ui.button("New seed").clicked() {
+                        lander_state.seed = rand::random();
+                        reset_requested = true;
+                    }
+                });
+                ui.checkbox(
+                    &mut editor_state.auto_pause_offscreen,
+                    "Auto-pause off-screen",
+                )
+                .on_hover_text("Pause the run when the lander flies out of view of the target");
+                let mut record_attempts = progress.record_attempts;
+                if ui
+                    .checkbox(&mut record_attempts, "Record attempts")
+                    .on_hover_text("Keep the path of every run for the attempt heatmap")
+                    .changed()
+                {
+                    let _ = persistence::set_record_attempts(record_attempts, &mut progress);
+                }
+                if ui.button("Clear attempt history").clicked() {
+                    let _ = persistence::clear_attempts(&mut history);
+                    heatmap_dirty.0 = true;
+                }
+                if ui.button("Challenge code...").clicked() {
+                    challenge_popup.show = !challenge_popup.show;
+                    challenge_popup.message = None;
+                    ui.close_menu();
+                }
+                ui.add(
+                    egui::Slider::new(&mut editor_state.countdown_duration, 0.0..=5.0)
+                        .step_by(1.0)
+                        .text("Countdown (s)"),
+                );
+            });
+            ui.menu_button("View", |ui| {
+                ui.checkbox(&mut view_options.show_control_hints, "Control hints (F1)");
+                ui.checkbox(&mut view_options.show_heatmap, "Attempt heatmap");
+                ui.checkbox(&mut view_options.show_ghost, "Best run ghost")
+                    .on_hover_text("Replay the level's best run alongside the current one");
+                ui.add(
+                    egui::Slider::new(&mut view_options.camera_lead, 0.0..=2.0)
+                        .text("Camera lead (s)"),
+                )
+                .on_hover_text("Look ahead in the direction of motion");
+                ui.horizontal(|ui| {
+                    ui.label(format!("Zoom: {:.0}%", camera_state.zoom() * 100.0))
+                        .on_hover_text("Scroll to zoom, drag with the middle button to pan");
+                    if ui
+                        .add_enabled(
+                            !camera_state.following || camera_state.zoom() != 1.0,
+                            egui::Button::new("Recenter"),
+                        )
+                        .on_hover_text("Reset the zoom and follow the lander again")
+                        .clicked()
+                    {
+                        camera_state.following = true;
+                        camera_state.scale = WORLD_TO_SCREEN_SCALE;
+                    }
+                });
+                ui.checkbox(&mut view_options.show_forces, "Force vectors");
+                if view_options.show_forces {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), "Gravity");
+                    ui.colored_label(egui::Color32::from_rgb(0, 191, 255), "Thrust");
+                    if current_level.config.has_wind() {
+                        ui.colored_label(egui::Color32::from_rgb(238, 130, 238), "Wind");
+                    }
+                    if current_level.config.physics.drag_coefficient > 0.0 {
+                        ui.colored_label(egui::Color32::from_rgb(192, 192, 192), "Drag");
+                    }
+                    ui.colored_label(egui::Color32::from_rgb(0, 255, 0), "Net force");
+                }
+                ui.checkbox(
+                    &mut view_options.show_motion_vectors,
+                    "Velocity and thrust arrows",
+                );
+                if view_options.show_motion_vectors {
+                    ui.colored_label(egui::Color32::from_rgb(0, 128, 0), "Velocity");
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), "Thrust");
+                    ui.add(
+                        egui::Slider::new(&mut view_options.motion_vector_gain, 0.25..=4.0)
+                            .logarithmic(true)
+                            .text("Arrow scale"),
+                    );
+                }
+                ui.checkbox(
+                    &mut view_options.show_range_axes,
+                    "Down-range/cross-range axes",
+                )
+                .on_hover_text("The line from the start to the landing target and across it");
+                ui.checkbox(&mut view_options.show_telemetry_plot, "Telemetry plots");
+                ui.checkbox(
+                    &mut view_options.show_hud,
+                    "Instruments (altitude, attitude)",
+                );
+                ui.checkbox(&mut view_options.show_sim_stats, "Integration stats");
+                ui.add(
+                    egui::Slider::new(&mut view_options.telemetry_decimals, 0..=4)
+                        .text("Telemetry decimals"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut view_options.scientific_threshold, 1.0e3..=1.0e9)
+                        .logarithmic(true)
+                        .text("Scientific above"),
+                )
+                .on_hover_text("Show telemetry larger than this in scientific notation");
+            });
+            ui.menu_button("Units", |ui| {
+                let (mut units, mut angle_units) = (editor_state.units, editor_state.angle_units);
+                ui.radio_value(&mut units, UnitSystem::Metric, "Metric (m, kg)");
+                ui.radio_value(&mut units, UnitSystem::Imperial, "Imperial (ft, lb)");
+                ui.separator();
+                ui.radio_value(&mut angle_units, AngleUnit::Degrees, "Degrees");
+                ui.radio_value(&mut angle_units, AngleUnit::Radians, "Radians");
+                if (units, angle_units) != (editor_state.units, editor_state.angle_units) {
+                    editor_state.units = units;
+                    editor_state.angle_units = angle_units;
+                    let _ = persistence::set_display_units(units, angle_units, &mut progress);
+                }
+            });
             if ui.button("About").clicked() {
                 about_popup.show = !about_popup.show;
             }
+            let mute_label = if audio_settings.muted {
+                "🔇 Muted"
+            } else {
+                "🔊 Sound"
+            };
+            ui.toggle_value(&mut audio_settings.muted, mute_label)
+                .on_hover_text("Mute the engine and crash sounds");
         });
     });
 
@@ -113,27 +412,31 @@ pub fn ui_system(
         if let Some(new_config) = level_manager.get_level(level_num) {
             editor_state.simulation_state = SimulationState::Stopped;
             script_engine.error_message = None;
-            editor_state.last_console_output.clear();
+            editor_state.console_lines.clear();
+            script_engine.clear_user_state();
 
             // Update current level
             current_level.config = new_config.clone();
 
             // Update script engine control type
-            match new_config.control_scheme {
-                ControlScheme::VerticalOnly => script_engine.set_control_type(ControlType::Simple),
-                ControlScheme::ThrustVector => {
-                    script_engine.set_control_type(ControlType::Vectored)
-                }
-            }
+            script_engine.set_level(&new_config);
 
             // Load default script for level
-            if let Ok(script) =
-                std::fs::read_to_string(format!("assets/scripts/level{}_default.rhai", level_num))
-            {
+            if let Ok(script) = std::fs::read_to_string(format!(
+                "assets/{}",
+                level_manager.default_script_path(level_num, &new_config)
+            )) {
                 editor_state.code = script;
             }
 
-            reset_simulation(&mut lander_state, &current_level, &mut camera_state);
+            playback.cancel();
+            reset_simulation(
+                &mut lander_state,
+                &current_level,
+                &mut camera_state,
+                &mut trajectory,
+                &mut script_engine,
+            );
             reset_flag.0 = true; // Reset lander visibility
             reset_vis.0 = true; // Reset visualization
         }
@@ -146,6 +449,16 @@ pub fn ui_system(
             // Level info
             ui.heading(&current_level.config.name);
             ui.label(&current_level.config.description);
+            let twr = current_level.config.initial_thrust_to_weight();
+            if twr < 1.0 {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    format!(
+                        "Note: the initial thrust-to-weight ratio is {:.2}, so the lander cannot hover until it burns off fuel.",
+                        twr
+                    ),
+                );
+            }
             ui.add_space(8.0);
             ui.horizontal(|ui| {
                 let rhai_link = Hyperlink::from_label_and_url("Rhai script", "https://rhai.rs")
@@ -162,16 +475,140 @@ pub fn ui_system(
                 ui.label("Available state variables:");
                 ui.label("• state[\"x\"] - horizontal position (meters)");
                 ui.label("• state[\"y\"] - vertical position (meters)");
+                ui.label("• state[\"agl\"] - height of the lander's base above the ground (meters)");
                 ui.label("• state[\"vx\"] - horizontal velocity (m/s)");
                 ui.label("• state[\"vy\"] - vertical velocity (m/s)");
                 ui.label("• state[\"rotation\"] - rotation angle (radians)");
                 ui.label("• state[\"angular_vel\"] - angular velocity (rad/s)");
+                ui.label("• state[\"t\"] - simulation time since the run started (seconds)");
                 ui.label("• state[\"fuel\"] - remaining fuel mass (kg)");
+                ui.label("• state[\"mass\"] - current total mass, dry plus fuel (kg)");
+                ui.label(
+                    "• state[\"g\"] - gravity at the lander's position, negative is down (m/s²)",
+                );
+                if current_level.config.wind.is_some() {
+                    ui.label(
+                        "• state[\"wind\"] - horizontal wind force, positive pushes right (N)",
+                    );
+                }
+                ui.label(
+                    "• state[\"target_x\"], state[\"target_y\"] - next waypoint, or the \
+                     landing target once all are passed (meters)",
+                );
+                ui.label(
+                    "• state[\"downrange\"], state[\"crossrange\"] - distance left to the \
+                     landing target along the line from the start, and the offset across it \
+                     (meters)",
+                );
+                ui.label(
+                    "• state[\"thrust_actual\"] - throttle the engine is actually at (0.0 to 1.0)",
+                );
+                if let ControlScheme::ThrustVector = current_level.config.control_scheme {
+                    ui.label(
+                        "• state[\"gimbal_actual\"] - gimbal angle the engine is actually at \
+                         (radians)",
+                    );
+                }
+                ui.label(
+                    "  The throttle and gimbal can only change so fast, so what you return is a \
+                     request: the engine moves towards it each step, and these show where it \
+                     has got to.",
+                );
+                let physics = &current_level.config.physics;
+                if physics.startup_delay > 0.0 {
+                    ui.label(format!(
+                        "• state[\"engine_ready\"] - false until thrust has been commanded for \
+                         {:.1} s after the engine was off",
+                        physics.startup_delay
+                    ));
+                }
+                if physics.min_throttle > 0.0 {
+                    ui.label(format!(
+                        "• The engine can't throttle below {:.0}%: lower commands snap to off \
+                         or to the minimum",
+                        physics.min_throttle * 100.0
+                    ));
+                }
+                ui.label("• state[\"low_fuel\"] - true once fuel drops below the warning level");
+                if current_level.config.physics.overheat.is_some() {
+                    ui.label("• state[\"engine_heat\"] - engine bell heat (1.0 = overheated)");
+                }
+                if let ControlScheme::ThrustVector = current_level.config.control_scheme {
+                    ui.label("• state[\"gimbal_limit\"] - maximum gimbal angle (radians)");
+                    ui.label("• state[\"gimbal_rate_limit\"] - maximum gimbal rate (rad/s)");
+                }
+                if physics.rcs_max_torque > 0.0 {
+                    ui.label(format!(
+                        "• state[\"rcs_fuel\"] - attitude thruster propellant left (kg, of {:.1})",
+                        physics.rcs_fuel
+                    ));
+                }
+                if current_level.config.physics.stage.is_some() {
+                    ui.label("• state[\"jettisoned\"] - true once the stage has been dropped");
+                }
+                if let Some(rate) = current_level.config.sensor_rate {
+                    ui.label(format!(
+                        "• Position, velocity and attitude update at {:.0} Hz and hold their \
+                         last reading in between",
+                        rate
+                    ));
+                    ui.label(
+                        "• state[\"fresh\"][\"y\"] etc. - true when that channel was just measured",
+                    );
+                }
                 ui.add_space(4.0);
 
                 ui.label("Helper functions:");
                 ui.label("• console(value) - print debug output");
+                ui.label("• console(label, value) - print \"label: value\"");
+                if let Some(stage) = &current_level.config.physics.stage {
+                    ui.label(format!(
+                        "• jettison() - drop the stage ({:.0} kg structure, {:.0} kg fuel)",
+                        stage.dry_mass, stage.fuel
+                    ));
+                }
+                if physics.rcs_max_torque > 0.0 {
+                    ui.label(format!(
+                        "• set_rcs(x) - fire the attitude thrusters this tick, -1.0 to 1.0 of \
+                         {:.0} N·m (positive turns counter-clockwise); 0 if not set",
+                        physics.rcs_max_torque
+                    ));
+                }
+                ui.label("• abort(\"message\") - end the run as a failure with this message");
+                ui.label("• console_fmt(\"x = {}, y = {}\", [x, y]) - print with {} placeholders");
+                ui.label(
+                    "• pid(kp, ki, kd, error, dt, \"name\") - PID controller output; keeps its \
+                     own integral and last error per name, cleared on reset",
+                );
+                match current_level.config.control_scheme {
+                    ControlScheme::VerticalOnly => ui.label(
+                        "• set_thrust(x) - set the output instead of returning it",
+                    ),
+                    ControlScheme::ThrustVector => ui.label(
+                        "• set_thrust(x), set_gimbal(a) - set the outputs instead of returning \
+                         them; any not set this tick are 0",
+                    ),
+                    ControlScheme::Translate => ui.label(
+                        "• set_thrust(x), set_lateral(x) - set the outputs instead of returning \
+                         them; any not set this tick are 0",
+                    ),
+                };
                 ui.label("• user_state - persistent variable storage");
+                ui.label(
+                    "• config[\"max_thrust\"], [\"gravity\"], [\"dry_mass\"], [\"isp\"] - level physics",
+                );
+                if let ControlScheme::ThrustVector = current_level.config.control_scheme {
+                    ui.label(
+                        "• config[\"moment_of_inertia\"], [\"thrust_moment_arm\"] - attitude dynamics",
+                    );
+                }
+                ui.label("• config[\"thrust_rate\"] - fastest throttle change (per second)");
+                if physics.rcs_max_torque > 0.0 {
+                    ui.label("• config[\"rcs_max_torque\"] - attitude thruster torque (N·m)");
+                }
+                ui.label(
+                    "• config[\"vx_max\"], [\"vy_max\"], [\"final_angle\"] - landing limits",
+                );
                 ui.add_space(4.0);
 
                 match current_level.config.control_scheme {
@@ -180,18 +617,83 @@ pub fn ui_system(
                         ui.label("Return a single number for thrust (0.0 to 1.0)");
                         ui.code("return 0.5; // 50% thrust");
                     }
+                    ControlScheme::ThrustVector if editor_state.attitude_assist => {
+                        ui.label("Control output (attitude assist on):");
+                        ui.label("Return an array: [thrust, sideways]");
+                        ui.label("• thrust: 0.0 to 1.0");
+                        ui.label("• sideways: -1.0 (move left) to 1.0 (move right)");
+                        ui.code("return [0.5, 0.2]; // 50% thrust, lean gently to the right");
+                    }
                     ControlScheme::ThrustVector => {
                         ui.label("Control output:");
                         ui.label("Return an array: [thrust, gimbal]");
                         ui.label("• thrust: 0.0 to 1.0");
-                        ui.label("• gimbal: -0.4 to 0.4 radians");
+                        let physics = &current_level.config.physics;
+                        ui.label(format!(
+                            "• gimbal: {:.2} to {:.2} radians",
+                            physics.gimbal_min, physics.gimbal_max
+                        ));
                         ui.code("return [0.5, 0.1]; // 50% thrust, 0.1 rad gimbal");
                     }
+                    ControlScheme::Translate => {
+                        ui.label("Control output:");
+                        ui.label("Return an array: [vertical_thrust, lateral_thrust]");
+                        ui.label("• vertical_thrust: 0.0 to 1.0");
+                        ui.label("• lateral_thrust: -1.0 (push left) to 1.0 (push right)");
+                        ui.code("return [0.5, -0.2]; // 50% thrust, 20% lateral to the left");
+                    }
                 }
             });
 
+            // Scratchpad for gains tried and observations, kept per level
+            ui.collapsing("Notes", |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut editor_state.notes)
+                        .hint_text("Gains tried, observations, ideas...")
+                        .desired_rows(4)
+                        .desired_width(f32::INFINITY),
+                );
+            });
+
             ui.add_space(8.0);
 
+            // Named solution slots for this level
+            let level_number = level_manager
+                .available_levels
+                .iter()
+                .find(|(_, name)| name == &current_level.config.name)
+                .map(|(number, _)| *number);
+            if let Some(level_number) = level_number {
+                solution_slots_ui(ui, level_number, &mut editor_state, &mut progress);
+            }
+
+            // Insert a template at the caret (or the end when the editor was never focused)
+            ui.menu_button("Snippets", |ui| {
+                for (title, snippet) in snippets::SNIPPETS {
+                    if ui.button(*title).clicked() {
+                        let char_count = editor_state.code.chars().count();
+                        let caret = editor_state.cursor.unwrap_or(char_count).min(char_count);
+                        let code = &mut editor_state.code;
+                        let byte_index = code
+                            .char_indices()
+                            .nth(caret)
+                            .map_or(code.len(), |(index, _)| index);
+                        code.insert_str(byte_index, snippet);
+                        let new_caret = caret + snippet.chars().count();
+                        editor_state.cursor = Some(new_caret);
+                        editor_state.pending_cursor = Some(new_caret);
+                        ui.close_menu();
+                    }
+                }
+            });
+
+            // Line to mark in the editor: the compile error, else the runtime error
+            let error_line = script_engine
+                .compile_error
+                .as_ref()
+                .or(script_engine.error_message.as_ref())
+                .and_then(|error| error.line);
+
             // Code editor
             egui::ScrollArea::vertical()
                 .max_height(CONSOLE_HEIGHT)
@@ -207,6 +709,9 @@ pub fn ui_system(
                             string,
                             "rs", // Using Rust syntax highlighting
                         );
+                        if let Some(line) = error_line {
+                            mark_line(&mut layout_job, line, ERROR_LINE_COLOR);
+                        }
                         layout_job.wrap.max_width = wrap_width;
                         ui.fonts(|f| f.layout_job(layout_job))
                     };
@@ -219,33 +724,131 @@ pub fn ui_system(
                         .desired_width(f32::INFINITY)
                         .layouter(&mut layouter);
 
-                    ui.add(editor);
+                    let mut output = editor.show(ui);
+                    if output.response.changed() {
+                        editor_state.code_edited_at = Some(ui.input(|i| i.time));
+                    }
+                    if let Some(caret) = editor_state.pending_cursor.take() {
+                        let ccursor = egui::text::CCursor::new(caret);
+                        output
+                            .state
+                            .cursor
+                            .set_char_range(Some(egui::text::CCursorRange::one(ccursor)));
+                        output.state.store(ui.ctx(), output.response.id);
+                        if std::mem::take(&mut editor_state.scroll_to_cursor) {
+                            let rect = output
+                                .galley
+                                .pos_from_ccursor(ccursor)
+                                .translate(output.galley_pos.to_vec2());
+                            ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                        }
+                    } else if let Some(range) = output.cursor_range {
+                        editor_state.cursor = Some(range.primary.ccursor.index);
+                    }
                     ui.add_space(8.0);
                 });
 
-            // Console output
-            ui.label("Console Output");
+            // Console output, accumulated over the run and capped at CONSOLE_LINE_LIMIT lines
+            let new_output = script_engine.take_console_output();
+            editor_state.console_lines.extend(new_output);
+            let excess = editor_state
+                .console_lines
+                .len()
+                .saturating_sub(CONSOLE_LINE_LIMIT);
+            editor_state.console_lines.drain(..excess);
+
+            ui.horizontal(|ui| {
+                ui.label("Console Output");
+                if ui.small_button("Clear").clicked() {
+                    editor_state.console_lines.clear();
+                }
+                ui.checkbox(&mut editor_state.console_autoscroll, "Autoscroll");
+            });
             egui::ScrollArea::vertical()
                 .id_salt(1234)
                 .max_height(editor_state.console_height)
+                .stick_to_bottom(editor_state.console_autoscroll)
                 .show(ui, |ui| {
-                    // Only update the console output if we get new messages
-                    if editor_state.simulation_state == SimulationState::Running {
-                        let new_output = script_engine.take_console_output();
-                        if !new_output.is_empty() {
-                            editor_state.last_console_output = new_output;
-                        }
-                    }
-
-                    // Display the last set of messages
-                    for line in &editor_state.last_console_output {
+                    for line in &editor_state.console_lines {
                         ui.colored_label(egui::Color32::GREEN, line);
                     }
                 });
 
+            // Live view of the script's persistent user_state
+            ui.collapsing("User State", |ui| {
+                if script_engine.user_state.is_empty() {
+                    ui.weak("(empty)");
+                } else {
+                    show_dynamic_map(ui, &script_engine.user_state);
+                }
+            });
+
+            // A picked script file replaces the editor contents. It compiles straight away,
+            // so a broken file shows its error but stays loaded to be fixed.
+            if let Some(imported) = take_imported_code() {
+                match imported {
+                    Ok(code) => {
+                        editor_state.code = code;
+                        editor_state.cursor = None;
+                        editor_state.code_edited_at = None;
+                    }
+                    Err(error) => editor_state
+                        .console_lines
+                        .push_back(format!("Import failed: {}", error)),
+                }
+            }
+
+            // Recompile once typing pauses, so syntax errors show up before anything runs.
+            // This only touches the scratch compile; a running simulation keeps its script.
+            if script_engine.compile_pending(&editor_state.code) {
+                let now = ui.input(|i| i.time);
+                let waited = editor_state.code_edited_at.map_or(COMPILE_DEBOUNCE, |t| now - t);
+                if waited >= COMPILE_DEBOUNCE {
+                    let _ = script_engine.compile_script(&editor_state.code);
+                    editor_state.code_edited_at = None;
+                } else {
+                    ui.ctx().request_repaint_after(std::time::Duration::from_secs_f64(
+                        COMPILE_DEBOUNCE - waited,
+                    ));
+                }
+            }
+            let compile_pending = script_engine.compile_pending(&editor_state.code);
+            let compile_ok = !compile_pending && script_engine.compile_error.is_none();
+
+            // Warn before the script gets too long to compile
+            let source_len = editor_state.code.chars().count();
+            let warn_len = (script_engine.max_source_len as f32 * SOURCE_LENGTH_WARNING) as usize;
+            if source_len > warn_len && source_len <= script_engine.max_source_len {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    format!(
+                        "Script is getting long: {} of {} characters.",
+                        source_len, script_engine.max_source_len
+                    ),
+                );
+            }
+
             // Status messages
-            if let Some(error) = &script_engine.error_message {
-                ui.colored_label(egui::Color32::RED, error);
+            if let Some(error) = &script_engine.compile_error {
+                show_script_error(ui, error, &mut editor_state);
+                if script_engine.has_active_script() {
+                    ui.label("Reset Simulation replays the last working script.");
+                }
+            } else if let Some(error) = &script_engine.error_message {
+                show_script_error(ui, error, &mut editor_state);
+            } else if editor_state.offscreen_paused
+                && editor_state.simulation_state == SimulationState::Paused
+            {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    "Auto-paused: the lander flew off-screen. Resume to keep going or reset.",
+                );
+            } else if let Some(message) =
+                script_engine.abort_message.as_ref().filter(|_| lander_state.crashed)
+            {
+                ui.colored_label(egui::Color32::RED, format!("Aborted: {}", message));
+            } else if lander_state.crashed && lander_state.engine_overheated {
+                ui.colored_label(egui::Color32::RED, "Mission failed! The engine overheated.");
             } else if lander_state.crashed {
                 ui.colored_label(egui::Color32::RED, &current_level.config.failure_message);
             } else if lander_state.landed {
@@ -263,44 +866,149 @@ pub fn ui_system(
             ui.horizontal(|ui| {
                 let (button_text, next_state) = match editor_state.simulation_state {
                     SimulationState::Stopped => ("Run", SimulationState::Running),
+                    SimulationState::Countdown => ("Cancel", SimulationState::Stopped),
                     SimulationState::Running => ("Pause", SimulationState::Paused),
                     SimulationState::Paused => ("Resume", SimulationState::Running),
                 };
 
-                if ui.button(button_text).clicked() {
+                // Starting or resuming needs code that compiles, unless flying by hand;
+                // pausing is always allowed
+                let manual = editor_state.control_source == ControlSource::Manual;
+                let can_press = compile_ok
+                    || manual
+                    || matches!(
+                        editor_state.simulation_state,
+                        SimulationState::Running | SimulationState::Countdown
+                    );
+                if ui
+                    .add_enabled(can_press, egui::Button::new(button_text))
+                    .clicked()
+                {
+                    // Leave review mode before the physics takes over again
+                    if playback.active {
+                        playback.stop(&mut lander_state);
+                    }
+
                     match editor_state.simulation_state {
                         SimulationState::Stopped => {
-                            // Starting from stopped state - compile and reset
-                            if script_engine.compile_script(&editor_state.code).is_ok() {
+                            // Starting from stopped state - use the new script and reset
+                            if script_engine.activate_script() || manual {
+                                script_engine.clear_user_state();
                                 reset_simulation(
                                     &mut lander_state,
                                     &current_level,
                                     &mut camera_state,
+                                    &mut trajectory,
+                                    &mut script_engine,
                                 );
-                                editor_state.simulation_state = next_state;
+                                editor_state.start_run();
                             }
                         }
+                        SimulationState::Countdown => {
+                            // Abort before the physics ever started
+                            editor_state.simulation_state = next_state;
+                        }
                         SimulationState::Running => {
                             // Pause the simulation
                             editor_state.simulation_state = next_state;
                         }
                         SimulationState::Paused => {
-                            // Resume from pause - recompile script but don't reset
-                            if script_engine.compile_script(&editor_state.code).is_ok() {
+                            // Resume from pause - pick up script edits but don't reset
+                            if script_engine.activate_script() || manual {
                                 editor_state.simulation_state = next_state;
+                                editor_state.offscreen_paused = false;
                             }
                         }
                     }
                 }
 
+                // Advance a single physics tick; picks up script edits like Resume does
+                let paused = editor_state.simulation_state == SimulationState::Paused;
+                if ui
+                    .add_enabled(
+                        paused && !playback.active && !compile_pending,
+                        egui::Button::new("Step"),
+                    )
+                    .on_hover_text("Advance one physics step")
+                    .clicked()
+                    && (script_engine.activate_script() || manual)
+                {
+                    editor_state.step_requested = true;
+                }
+
+                let source_text = if manual {
+                    "Fly with Script"
+                } else {
+                    "Fly Manually"
+                };
+                if ui
+                    .button(source_text)
+                    .on_hover_text("Press F1 for the manual controls")
+                    .clicked()
+                {
+                    editor_state.control_source = if manual {
+                        ControlSource::Script
+                    } else {
+                        ControlSource::Manual
+                    };
+                }
+
                 if ui.button("Reset Simulation").clicked() {
                     reset_requested = true;
                 }
 
+                // Simulation speed, independent of pausing
+                for scale in TIME_SCALES {
+                    let label = format!("{}x", scale);
+                    if ui
+                        .selectable_label(editor_state.time_scale == scale, label)
+                        .clicked()
+                    {
+                        editor_state.time_scale = scale;
+                    }
+                }
+
                 if ui.button("Reset Code").clicked() {
                     editor_state.show_reset_confirmation = true;
                 }
 
+                let can_review = !matches!(
+                    editor_state.simulation_state,
+                    SimulationState::Running | SimulationState::Countdown
+                )
+                    && trajectory.samples.len() > 1;
+                let review_text = if playback.active {
+                    "End Review"
+                } else {
+                    "Review"
+                };
+                if ui
+                    .add_enabled(can_review, egui::Button::new(review_text))
+                    .clicked()
+                {
+                    if playback.active {
+                        playback.stop(&mut lander_state);
+                        if lander_state.crashed {
+                            // Replay the explosion so the lander is hidden again
+                            camera_state.explosion_spawned = false;
+                        }
+                    } else {
+                        playback.start(&lander_state, &trajectory);
+                        reset_flag.0 = true; // Show the lander while reviewing
+                    }
+                }
+
+                if ui
+                    .add_enabled(
+                        compile_ok && !monte_carlo.running(),
+                        egui::Button::new(format!("Run {} Trials", TRIAL_COUNT)),
+                    )
+                    .on_hover_text("Fly the script headlessly over the level's dispersion")
+                    .clicked()
+                {
+                    monte_carlo.start(&editor_state.code, &current_level.config);
+                }
+
                 if ui.button("Hint").clicked() {
                     about_popup.show = false; // Hide other popups
                     hint_popup.show = true;
@@ -316,10 +1024,59 @@ pub fn ui_system(
                         export_code(&editor_state.code, *level_num);
                     }
                 }
+
+                if ui
+                    .button("Import")
+                    .on_hover_text("Load a .rhai file into the editor")
+                    .clicked()
+                {
+                    import_code();
+                }
+
+                #[cfg(target_arch = "wasm32")]
+                if ui
+                    .button("Copy Share Link")
+                    .on_hover_text("Copy a link that opens this level with this code")
+                    .clicked()
+                {
+                    let link = level_manager
+                        .available_levels
+                        .iter()
+                        .find(|(_, name)| name == &current_level.config.name)
+                        .and_then(|(level_num, _)| {
+                            crate::share::share_url(*level_num, &editor_state.code)
+                        });
+                    if let Some(link) = link {
+                        ui.ctx().output_mut(|output| output.copied_text = link);
+                    }
+                }
+
+                if ui
+                    .add_enabled(
+                        !telemetry.samples.is_empty(),
+                        egui::Button::new("Export Telemetry"),
+                    )
+                    .clicked()
+                {
+                    if let Some((level_num, _)) = level_manager
+                        .available_levels
+                        .iter()
+                        .find(|(_, name)| name == &current_level.config.name)
+                    {
+                        save_text_file(
+                            &format!("level{}_telemetry.csv", level_num),
+                            &telemetry.to_csv(),
+                            "text/csv",
+                        );
+                    }
+                }
             });
         });
 
     // Bottom telemetry panel
+    let mut show_trail = view_options.show_trail;
+    let readout = |value: f32| format_telemetry(value, &view_options);
+    let (units, angle_units) = (editor_state.units, editor_state.angle_units);
     egui::TopBottomPanel::bottom("telemetry")
         .min_height(80.0)
         .show(contexts.ctx_mut(), |ui| {
@@ -327,8 +1084,18 @@ pub fn ui_system(
                 // Position
                 ui.vertical(|ui| {
                     ui.label("Position:");
-                    ui.label(format!("X: {:.1} m", lander_state.position.x));
-                    ui.label(format!("Y: {:.1} m", lander_state.position.y));
+                    for (axis, value) in [
+                        ("X", lander_state.position.x),
+                        ("Y", lander_state.position.y),
+                    ] {
+                        ui.label(format!(
+                            "{}: {} {}",
+                            axis,
+                            readout(units.length(value)),
+                            units.length_unit()
+                        ));
+                    }
+                    ui.checkbox(&mut show_trail, "Trail");
                 });
 
                 ui.add_space(20.0);
@@ -336,20 +1103,45 @@ pub fn ui_system(
                 // Velocity
                 ui.vertical(|ui| {
                     ui.label("Velocity:");
-                    ui.label(format!("VX: {:.1} m/s", lander_state.velocity.x));
-                    ui.label(format!("VY: {:.1} m/s", lander_state.velocity.y));
+                    for (axis, value) in [
+                        ("VX", lander_state.velocity.x),
+                        ("VY", lander_state.velocity.y),
+                    ] {
+                        ui.label(format!(
+                            "{}: {} {}",
+                            axis,
+                            readout(units.length(value)),
+                            units.speed_unit()
+                        ));
+                    }
                 });
 
                 ui.add_space(20.0);
 
+                if editor_state.precision_assist {
+                    let time_scale = precision_time_scale(&lander_state, &current_level.config);
+                    if time_scale < 1.0 {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!("Precision assist: {:.0}% speed", time_scale * 100.0),
+                        );
+                        ui.add_space(20.0);
+                    }
+                }
+
                 // Rotation (only show for thrust vector control)
                 if let ControlScheme::ThrustVector = current_level.config.control_scheme {
                     ui.vertical(|ui| {
                         ui.label("Rotation:");
-                        ui.label(format!("Angle: {:.1}°", lander_state.rotation.to_degrees()));
                         ui.label(format!(
-                            "Gimbal: {:.1}°",
-                            lander_state.gimbal_angle.to_degrees()
+                            "Angle: {}{}",
+                            readout(angle_units.angle(lander_state.rotation)),
+                            angle_units.unit()
+                        ));
+                        ui.label(format!(
+                            "Gimbal: {}{}",
+                            readout(angle_units.angle(lander_state.gimbal_angle)),
+                            angle_units.unit()
                         ));
                     });
                     ui.add_space(20.0);
@@ -362,11 +1154,87 @@ pub fn ui_system(
                         "Thrust: {}%",
                         (lander_state.thrust_level * 100.0) as i32
                     ));
-                    ui.label(format!("Fuel: {:.1} kg", lander_state.fuel));
+                    if let ControlScheme::Translate = current_level.config.control_scheme {
+                        ui.label(format!(
+                            "Lateral: {}%",
+                            (lander_state.lateral_thrust * 100.0) as i32
+                        ));
+                    }
+                    let fuel_text = format!(
+                        "Fuel: {} {}",
+                        readout(units.mass(lander_state.fuel)),
+                        units.mass_unit()
+                    );
+                    if lander_state.is_low_fuel(&current_level.config) {
+                        // Flash the readout twice a second while fuel is low
+                        let flash_on = (ui.input(|i| i.time) * 2.0) as i64 % 2 == 0;
+                        let color = if flash_on {
+                            egui::Color32::RED
+                        } else {
+                            egui::Color32::YELLOW
+                        };
+                        ui.colored_label(color, format!("{} (LOW)", fuel_text));
+                    } else {
+                        ui.label(fuel_text);
+                    }
+
+                    if current_level.config.physics.overheat.is_some() {
+                        let heat = lander_state.heat_fraction(&current_level.config);
+                        let (color, text) = if lander_state.engine_overheated {
+                            (egui::Color32::RED, "OVERHEAT".to_string())
+                        } else {
+                            let color = if heat > 0.75 {
+                                egui::Color32::from_rgb(230, 120, 0)
+                            } else {
+                                egui::Color32::from_rgb(200, 160, 0)
+                            };
+                            (color, format!("Heat: {}%", (heat * 100.0) as i32))
+                        };
+                        ui.add(
+                            egui::ProgressBar::new(heat.clamp(0.0, 1.0))
+                                .desired_width(100.0)
+                                .fill(color)
+                                .text(text),
+                        );
+                    }
                 });
+
+                // Instantaneous wind and turbulence force, with an arrow for the direction
+                if current_level.config.has_wind() {
+                    let wind = lander_state.wind_force(&current_level.config);
+                    ui.add_space(20.0);
+                    ui.vertical(|ui| {
+                        ui.label("Wind:");
+                        for (axis, value) in [("Fx", wind.x), ("Fy", wind.y)] {
+                            ui.label(format!(
+                                "{}: {} {}",
+                                axis,
+                                readout(units.force(value)),
+                                units.force_unit()
+                            ));
+                        }
+
+                        let (rect, _) = ui.allocate_exact_size(
+                            egui::vec2(2.0 * WIND_ARROW_LENGTH, 16.0),
+                            egui::Sense::hover(),
+                        );
+                        let max_wind = current_level.config.max_wind_force();
+                        if max_wind > 0.0 {
+                            // Screen y points down, so flip the vertical component
+                            let arrow = egui::vec2(wind.x, -wind.y) / max_wind * WIND_ARROW_LENGTH;
+                            ui.painter().arrow(
+                                rect.center() - arrow / 2.0,
+                                arrow,
+                                egui::Stroke::new(2.0, egui::Color32::from_rgb(238, 130, 238)),
+                            );
+                        }
+                    });
+                }
             });
         });
 
+    view_options.show_trail = show_trail;
+
     // Add the confirmation dialog for "Reset Code"
     if editor_state.show_reset_confirmation {
         egui::Window::new("Confirm Reset")
@@ -382,6 +1250,7 @@ pub fn ui_system(
                         {
                             editor_state.code = script_asset.0.clone();
                         }
+                        script_engine.clear_user_state();
                         editor_state.show_reset_confirmation = false;
                     }
                     if ui.button("No").clicked() {
@@ -393,10 +1262,25 @@ pub fn ui_system(
 
     // Handle reset request
     if reset_requested {
-        editor_state.simulation_state = SimulationState::Stopped;
+        // With a compile error in the editor, re-watch the last good run instead
+        let replay_last_good =
+            script_engine.compile_error.is_some() && script_engine.has_active_script();
+        if replay_last_good {
+            editor_state.start_run();
+        } else {
+            editor_state.simulation_state = SimulationState::Stopped;
+        }
         script_engine.error_message = None;
-        editor_state.last_console_output.clear(); // Clear console history on reset
-        reset_simulation(&mut lander_state, &current_level, &mut camera_state);
+        script_engine.clear_user_state();
+        editor_state.console_lines.clear(); // Clear console history on reset
+        playback.cancel();
+        reset_simulation(
+            &mut lander_state,
+            &current_level,
+            &mut camera_state,
+            &mut trajectory,
+            &mut script_engine,
+        );
         reset_flag.0 = true; // Set the flag to trigger visibility reset
     }
 }
@@ -408,49 +1292,174 @@ pub enum GameState {
     #[default]
     LevelSelect,
     Playing,
+    LevelEditor, // Designing a custom level
 }
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct LevelCompletePopup {
     pub show: bool,
     pub completed_level: usize,
 }
 
-impl Default for LevelCompletePopup {
-    fn default() -> Self {
-        Self {
-            show: false,
-            completed_level: 0,
-        }
+// Point the editor and script engine at another level, loading its saved code and
+// notes. The caller resets the simulation afterwards.
+#[allow(clippy::too_many_arguments)]
+pub fn switch_level(
+    number: usize,
+    new_config: LevelConfig,
+    level_manager: &LevelManager,
+    editor_state: &mut EditorState,
+    current_level: &mut CurrentLevel,
+    script_engine: &mut ScriptEngine,
+    progress: &Persistent<LevelProgress>,
+    asset_server: &AssetServer,
+    script_assets: &Assets<ScriptAsset>,
+) {
+    editor_state.simulation_state = SimulationState::Stopped;
+    script_engine.clear_active_script();
+    script_engine.clear_user_state();
+    editor_state.notes = persistence::get_notes(number, progress);
+    editor_state.units = progress.units;
+    editor_state.angle_units = progress.angle_units;
+
+    // Update script engine control type
+    script_engine.set_level(&new_config);
+
+    // Load default script for this level
+    let default_script_handle =
+        asset_server.load(level_manager.default_script_path(number, &new_config));
+    editor_state.default_script_handle = default_script_handle.clone();
+
+    // Try to get saved code or use default
+    if let Some(saved_code) = persistence::get_editor_state(number, progress) {
+        editor_state.code = saved_code;
+    } else if let Some(script_asset) = script_assets.get(&default_script_handle) {
+        editor_state.code = script_asset.0.clone();
     }
+
+    current_level.config = new_config;
 }
 
-pub fn level_select_ui(
-    mut contexts: EguiContexts,
+// Go straight to the level a share link names, with its code as a new solution slot so
+// the user's own saved code for the level isn't overwritten. Runs once, at startup.
+#[cfg(target_arch = "wasm32")]
+#[allow(clippy::too_many_arguments)]
+pub fn open_share_link(
+    mut checked: Local<bool>,
     level_manager: Res<LevelManager>,
-    progress: Res<Persistent<LevelProgress>>,
+    mut progress: ResMut<Persistent<LevelProgress>>,
     mut editor_state: ResMut<EditorState>,
     mut current_level: ResMut<CurrentLevel>,
     mut state: ResMut<NextState<GameState>>,
     mut camera_state: ResMut<CameraState>,
     mut lander_state: ResMut<LanderState>,
-    mut reset_flag: ResMut<ResetVisibilityFlag>,
-    mut reset_vis: ResMut<ResetVisualization>,
+    (mut reset_flag, mut reset_vis): (ResMut<ResetVisibilityFlag>, ResMut<ResetVisualization>),
     mut script_engine: ResMut<ScriptEngine>,
-    mut about_popup: ResMut<AboutPopupState>,
     asset_server: Res<AssetServer>,
     script_assets: Res<Assets<ScriptAsset>>,
+    mut trajectory: ResMut<Trajectory>,
 ) {
-    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
-        ui.vertical_centered(|ui| {
+    if std::mem::replace(&mut *checked, true) {
+        return;
+    }
+    let shared = match crate::share::from_page_url() {
+        Some(Ok(shared)) => shared,
+        Some(Err(error)) => {
+            warn!("Ignoring share link: {}", error);
+            return;
+        }
+        None => return,
+    };
+    crate::share::clear_page_url();
+    let Some(new_config) = level_manager.get_level(shared.level) else {
+        warn!("Ignoring share link: there is no level {}", shared.level);
+        return;
+    };
+    if !persistence::is_level_available(shared.level, &level_manager, &progress) {
+        warn!(
+            "Ignoring share link: level {} isn't unlocked yet",
+            shared.level
+        );
+        return;
+    }
+
+    // Opening the same link twice selects the slot it made the first time
+    if let Some(code) = shared.code {
+        let existing = persistence::get_script_slots(shared.level, &progress)
+            .iter()
+            .position(|slot| slot.code == code);
+        let _ = match existing {
+            Some(index) => persistence::select_script_slot(shared.level, index, &mut progress),
+            None => persistence::add_script_slot(
+                shared.level,
+                "Shared link".into(),
+                code,
+                &mut progress,
+            ),
+        };
+    }
+    switch_level(
+        shared.level,
+        new_config,
+        &level_manager,
+        &mut editor_state,
+        &mut current_level,
+        &mut script_engine,
+        &progress,
+        &asset_server,
+        &script_assets,
+    );
+    reset_simulation(
+        &mut lander_state,
+        &current_level,
+        &mut camera_state,
+        &mut trajectory,
+        &mut script_engine,
+    );
+    reset_flag.0 = true;
+    reset_vis.0 = true;
+    state.set(GameState::Playing);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn level_select_ui(
+    mut contexts: EguiContexts,
+    level_manager: Res<LevelManager>,
+    progress: Res<Persistent<LevelProgress>>,
+    mut editor_state: ResMut<EditorState>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut state: ResMut<NextState<GameState>>,
+    mut camera_state: ResMut<CameraState>,
+    mut lander_state: ResMut<LanderState>,
+    mut reset_flag: ResMut<ResetVisibilityFlag>,
+    mut reset_vis: ResMut<ResetVisualization>,
+    mut script_engine: ResMut<ScriptEngine>,
+    (mut about_popup, mut stats_popup): (ResMut<AboutPopupState>, ResMut<StatsPopupState>),
+    asset_server: Res<AssetServer>,
+    script_assets: Res<Assets<ScriptAsset>>,
+    (mut trajectory, mut playback, mut level_editor): (
+        ResMut<Trajectory>,
+        ResMut<TrajectoryPlayback>,
+        ResMut<LevelEditorState>,
+    ),
+) {
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.vertical_centered(|ui| {
             ui.heading("Level Select");
             ui.add_space(20.0);
 
             for (number, name) in &level_manager.available_levels {
-                let available = persistence::is_level_available(*number, &progress);
+                let available = persistence::is_level_available(*number, &level_manager, &progress);
                 let completed = persistence::is_level_completed(*number, &progress);
 
-                let text = format!("Level {}: {}", number, name);
+                let text = match persistence::get_best_score(*number, &progress) {
+                    Some(best) => format!(
+                        "Level {}: {}  (best: {:.1} kg fuel, {:.1} s)",
+                        number, name, best.fuel_remaining, best.time
+                    ),
+                    None if levels::is_custom_level(*number) => format!("Custom: {}", name),
+                    None => format!("Level {}: {}", number, name),
+                };
                 let mut button = egui::Button::new(text);
 
                 if !available {
@@ -461,34 +1470,25 @@ pub fn level_select_ui(
 
                 if available && ui.add(button).clicked() {
                     if let Some(new_config) = level_manager.get_level(*number) {
-                        editor_state.simulation_state = SimulationState::Stopped;
-                        current_level.config = new_config.clone();
-
-                        // Update script engine control type
-                        match new_config.control_scheme {
-                            ControlScheme::VerticalOnly => {
-                                script_engine.set_control_type(ControlType::Simple)
-                            }
-                            ControlScheme::ThrustVector => {
-                                script_engine.set_control_type(ControlType::Vectored)
-                            }
-                        }
-
-                        // Load default script for this level
-                        let default_script_handle =
-                            asset_server.load(format!("scripts/level{}_default.rhai", number));
-                        editor_state.default_script_handle = default_script_handle.clone();
-
-                        // Try to get saved code or use default
-                        if let Some(saved_code) = persistence::get_editor_state(*number, &progress)
-                        {
-                            editor_state.code = saved_code;
-                        } else if let Some(script_asset) = script_assets.get(&default_script_handle)
-                        {
-                            editor_state.code = script_asset.0.clone();
-                        }
-
-                        reset_simulation(&mut lander_state, &current_level, &mut camera_state);
+                        switch_level(
+                            *number,
+                            new_config,
+                            &level_manager,
+                            &mut editor_state,
+                            &mut current_level,
+                            &mut script_engine,
+                            &progress,
+                            &asset_server,
+                            &script_assets,
+                        );
+                        playback.cancel();
+                        reset_simulation(
+                            &mut lander_state,
+                            &current_level,
+                            &mut camera_state,
+                            &mut trajectory,
+                            &mut script_engine,
+                        );
                         reset_flag.0 = true;
                         reset_vis.0 = true;
                         state.set(GameState::Playing);
@@ -496,6 +1496,26 @@ pub fn level_select_ui(
                 }
             }
 
+            // Design a new level, starting from the one last played, or change a saved one
+            ui.add_space(20.0);
+            if ui.button("New Custom Level").clicked() {
+                let mut config = current_level.config.clone();
+                config.name = "My Level".into();
+                level_editor.open(config, None);
+                state.set(GameState::LevelEditor);
+            }
+            for (number, name) in &level_manager.available_levels {
+                if !levels::is_custom_level(*number) {
+                    continue;
+                }
+                if ui.button(format!("Edit \"{}\"", name)).clicked() {
+                    if let Some(config) = level_manager.get_level(*number) {
+                        level_editor.open(config, Some(number - levels::CUSTOM_LEVEL_START));
+                        state.set(GameState::LevelEditor);
+                    }
+                }
+            }
+
             ui.add_space(30.0);
 
             ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
@@ -506,6 +1526,10 @@ pub fn level_select_ui(
                             std::process::exit(0);
                         }
                         ui.add_space(20.0);
+                        if ui.button("Stats").clicked() {
+                            stats_popup.show = !stats_popup.show;
+                        }
+                        ui.add_space(20.0);
                         if ui.button("About").clicked() {
                             about_popup.show = !about_popup.show;
                         }
@@ -514,23 +1538,1014 @@ pub fn level_select_ui(
 
                 #[cfg(target_arch = "wasm32")]
                 {
-                    if ui.button("About").clicked() {
-                        about_popup.show = true;
+                    ui.horizontal(|ui| {
+                        if ui.button("Stats").clicked() {
+                            stats_popup.show = true;
+                        }
+                        ui.add_space(20.0);
+                        if ui.button("About").clicked() {
+                            about_popup.show = true;
+                        }
+                    });
+                }
+            });
+        });
+    });
+}
+
+// Side-by-side comparison of the best runs on two levels
+pub fn stats_popup(
+    mut contexts: EguiContexts,
+    mut popup: ResMut<StatsPopupState>,
+    level_manager: Res<LevelManager>,
+    progress: Res<Persistent<LevelProgress>>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    if !popup.show {
+        return;
+    }
+
+    egui::Window::new("Best Runs")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.columns(2, |columns| {
+                let StatsPopupState {
+                    left_level,
+                    right_level,
+                    ..
+                } = &mut *popup;
+                for (column, (ui, selected)) in columns
+                    .iter_mut()
+                    .zip([left_level, right_level])
+                    .enumerate()
+                {
+                    let selected_name = level_manager
+                        .available_levels
+                        .iter()
+                        .find(|(number, _)| number == selected)
+                        .map(|(_, name)| name.clone())
+                        .unwrap_or_default();
+
+                    egui::ComboBox::from_id_salt(("stats_level", column))
+                        .selected_text(format!("Level {}: {}", selected, selected_name))
+                        .show_ui(ui, |ui| {
+                            for (number, name) in &level_manager.available_levels {
+                                ui.selectable_value(
+                                    selected,
+                                    *number,
+                                    format!("Level {}: {}", number, name),
+                                );
+                            }
+                        });
+                    ui.add_space(8.0);
+
+                    match persistence::get_best_score(*selected, &progress) {
+                        Some(score) => {
+                            ui.label(format!("Fuel remaining: {:.1} kg", score.fuel_remaining));
+                            ui.label(format!("Time: {:.1} s", score.time));
+                            ui.label(format!("Touchdown speed: {:.2} m/s", score.touchdown_speed));
+                            ui.label(format!("Accuracy: {:.2} m", score.accuracy));
+                            ui.add_space(4.0);
+                            draw_trajectory_thumbnail(ui, &score.trajectory);
+                        }
+                        None => {
+                            ui.label("No completed runs yet.");
+                        }
                     }
                 }
             });
+
+            ui.add_space(8.0);
+            ui.vertical_centered(|ui| {
+                if ui.button("Close").clicked() || keys.just_pressed(KeyCode::Escape) {
+                    popup.show = false;
+                }
+            });
+        });
+}
+
+// Draw a recorded (x, y) path scaled to fit a small box
+fn draw_trajectory_thumbnail(ui: &mut egui::Ui, path: &[(f32, f32)]) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(220.0, 140.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 4.0, egui::Color32::from_gray(20));
+
+    if path.len() < 2 {
+        return;
+    }
+
+    let (mut x_min, mut x_max) = (f32::INFINITY, f32::NEG_INFINITY);
+    let (mut y_min, mut y_max) = (0.0_f32, f32::NEG_INFINITY); // Always include the ground
+    for (x, y) in path {
+        x_min = x_min.min(*x);
+        x_max = x_max.max(*x);
+        y_min = y_min.min(*y);
+        y_max = y_max.max(*y);
+    }
+    // Keep the aspect ratio so descent angles look right
+    let span = (x_max - x_min).max(y_max - y_min).max(1.0);
+    let inner = rect.shrink(8.0);
+    let scale = (inner.width() / span).min(inner.height() / span);
+    let x_center = (x_min + x_max) / 2.0;
+
+    let to_screen = |x: f32, y: f32| {
+        egui::pos2(
+            inner.center().x + (x - x_center) * scale,
+            inner.bottom() - (y - y_min) * scale,
+        )
+    };
+
+    painter.line_segment(
+        [
+            to_screen(x_center - span, 0.0),
+            to_screen(x_center + span, 0.0),
+        ],
+        egui::Stroke::new(1.0, egui::Color32::GRAY),
+    );
+    let points: Vec<egui::Pos2> = path.iter().map(|(x, y)| to_screen(*x, *y)).collect();
+    painter.add(egui::Shape::line(
+        points,
+        egui::Stroke::new(1.5, egui::Color32::LIGHT_GREEN),
+    ));
+}
+
+// Pick, add, rename and delete the level's saved solutions. The editor's code is saved
+// to the current slot before switching away from it.
+fn solution_slots_ui(
+    ui: &mut egui::Ui,
+    level: usize,
+    editor_state: &mut EditorState,
+    progress: &mut Persistent<LevelProgress>,
+) {
+    let names: Vec<String> = persistence::get_script_slots(level, progress)
+        .iter()
+        .map(|slot| slot.name.clone())
+        .collect();
+    let active = persistence::get_active_slot(level, progress);
+    let active_name = names
+        .get(active)
+        .cloned()
+        .unwrap_or(persistence::DEFAULT_SLOT_NAME.into());
+
+    ui.horizontal(|ui| {
+        ui.label("Solution:");
+
+        if let Some(name) = &mut editor_state.renaming_slot {
+            let response = ui.text_edit_singleline(name);
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if submitted || ui.small_button("OK").clicked() {
+                let name = name.trim().to_string();
+                if !name.is_empty() {
+                    save_slot(level, editor_state, progress);
+                    let _ = persistence::rename_script_slot(level, active, name, progress);
+                }
+                editor_state.renaming_slot = None;
+            } else if ui.small_button("Cancel").clicked() {
+                editor_state.renaming_slot = None;
+            }
+            return;
+        }
+
+        let mut selected = active;
+        egui::ComboBox::from_id_salt("solution_slot")
+            .selected_text(&active_name)
+            .show_ui(ui, |ui| {
+                if names.is_empty() {
+                    ui.selectable_value(&mut selected, 0, persistence::DEFAULT_SLOT_NAME);
+                }
+                for (index, name) in names.iter().enumerate() {
+                    ui.selectable_value(&mut selected, index, name);
+                }
+            });
+        if selected != active {
+            save_slot(level, editor_state, progress);
+            let _ = persistence::select_script_slot(level, selected, progress);
+            load_slot(level, editor_state, progress);
+        }
+
+        if ui
+            .small_button("New")
+            .on_hover_text("Save a copy of this code as a new solution")
+            .clicked()
+        {
+            save_slot(level, editor_state, progress);
+            let count = persistence::get_script_slots(level, progress).len();
+            let _ = persistence::add_script_slot(
+                level,
+                format!("Solution {}", count + 1),
+                editor_state.code.clone(),
+                progress,
+            );
+        }
+        if ui.small_button("Rename").clicked() {
+            editor_state.renaming_slot = Some(active_name.clone());
+        }
+        if ui
+            .add_enabled(names.len() > 1, egui::Button::new("Delete").small())
+            .on_hover_text("Delete this solution")
+            .clicked()
+        {
+            let _ = persistence::delete_script_slot(level, active, progress);
+            load_slot(level, editor_state, progress);
+        }
+    });
+}
+
+fn save_slot(level: usize, editor_state: &EditorState, progress: &mut Persistent<LevelProgress>) {
+    let _ = persistence::save_editor_state(
+        level,
+        editor_state.code.clone(),
+        editor_state.notes.clone(),
+        progress,
+    );
+}
+
+// Put the active slot's code in the editor
+fn load_slot(level: usize, editor_state: &mut EditorState, progress: &Persistent<LevelProgress>) {
+    if let Some(code) = persistence::get_editor_state(level, progress) {
+        editor_state.code = code;
+        editor_state.cursor = None;
+        editor_state.code_edited_at = None;
+    }
+}
+
+// Script error with a button that puts the editor caret on the line it points at
+fn show_script_error(ui: &mut egui::Ui, error: &ScriptError, editor_state: &mut EditorState) {
+    ui.colored_label(egui::Color32::RED, &error.message);
+    let Some(line) = error.line else {
+        return;
+    };
+    if ui.small_button(format!("Go to line {}", line)).clicked() {
+        let line_start: usize = editor_state
+            .code
+            .split('\n')
+            .take(line.saturating_sub(1))
+            .map(|text| text.chars().count() + 1)
+            .sum();
+        let caret = line_start + error.col.unwrap_or(1).saturating_sub(1);
+        editor_state.cursor = Some(caret);
+        editor_state.pending_cursor = Some(caret);
+        editor_state.scroll_to_cursor = true;
+    }
+}
+
+// Give one line (1-based) of a laid out text a background, splitting the sections that
+// straddle its ends
+fn mark_line(job: &mut egui::text::LayoutJob, line: usize, color: egui::Color32) {
+    let mut start = 0;
+    for text in job.text.split_inclusive('\n').take(line.saturating_sub(1)) {
+        start += text.len();
+    }
+    let Some(text) = job.text[start..].split('\n').next() else {
+        return;
+    };
+    let end = start + text.len();
+
+    let mut sections = Vec::with_capacity(job.sections.len() + 2);
+    for section in job.sections.drain(..) {
+        let range = section.byte_range.clone();
+        let cuts = [
+            range.start,
+            start.clamp(range.start, range.end),
+            end.clamp(range.start, range.end),
+            range.end,
+        ];
+        let mut first = true;
+        for (index, cut) in cuts.windows(2).enumerate() {
+            if cut[0] >= cut[1] {
+                continue;
+            }
+            let mut part = section.clone();
+            part.byte_range = cut[0]..cut[1];
+            if !first {
+                part.leading_space = 0.0;
+            }
+            if index == 1 {
+                part.format.background = color;
+            }
+            sections.push(part);
+            first = false;
+        }
+    }
+    job.sections = sections;
+}
+
+// Render a Rhai map as a key/value tree, expanding nested maps and arrays
+fn show_dynamic_map(ui: &mut egui::Ui, map: &rhai::Map) {
+    show_dynamic_entries(ui, map.iter().map(|(key, value)| (key.to_string(), value)));
+}
+
+// Plain values go in an aligned key/value table, with nested maps and arrays as
+// collapsible sections under it
+fn show_dynamic_entries<'a>(
+    ui: &mut egui::Ui,
+    entries: impl Iterator<Item = (String, &'a Dynamic)>,
+) {
+    let (nested, plain): (Vec<_>, Vec<_>) =
+        entries.partition(|(_, value)| value.is_map() || value.is_array());
+    if !plain.is_empty() {
+        egui::Grid::new(ui.next_auto_id())
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                for (label, value) in &plain {
+                    ui.label(label.as_str());
+                    ui.monospace(format_dynamic(value));
+                    ui.end_row();
+                }
+            });
+    }
+    for (label, value) in nested {
+        if let Some(map) = value.read_lock::<rhai::Map>() {
+            ui.collapsing(format!("{} {{{}}}", label, map.len()), |ui| {
+                show_dynamic_map(ui, &map)
+            });
+        } else if let Some(array) = value.read_lock::<rhai::Array>() {
+            ui.collapsing(format!("{} [{}]", label, array.len()), |ui| {
+                show_dynamic_entries(
+                    ui,
+                    array
+                        .iter()
+                        .enumerate()
+                        .map(|(index, item)| (index.to_string(), item)),
+                )
+            });
+        }
+    }
+}
+
+fn format_dynamic(value: &Dynamic) -> String {
+    if let Ok(number) = value.as_float() {
+        format!("{:.4}", number)
+    } else if value.is_string() {
+        format!("{:?}", value.to_string())
+    } else if value.is_unit() {
+        "()".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+// Telemetry number with the configured precision, in scientific notation when large
+fn format_telemetry(value: f32, view_options: &ViewOptions) -> String {
+    let decimals = view_options.telemetry_decimals;
+    if value.abs() >= view_options.scientific_threshold {
+        format!("{:.*e}", decimals, value)
+    } else {
+        format!("{:.*}", decimals, value)
+    }
+}
+
+// Debug panel exposing the integration loop: step size, steps per frame and rates
+pub fn sim_stats_overlay(
+    mut contexts: EguiContexts,
+    view_options: Res<ViewOptions>,
+    stats: Res<SimStats>,
+) {
+    if !view_options.show_sim_stats {
+        return;
+    }
+
+    egui::Window::new("Integration")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::LEFT_TOP, egui::vec2(10.0, 40.0))
+        .show(contexts.ctx_mut(), |ui| {
+            egui::Grid::new("sim_stats").num_columns(2).show(ui, |ui| {
+                ui.label("Step dt:");
+                ui.monospace(format!("{:.2} ms", stats.step_dt * 1000.0));
+                ui.end_row();
+                ui.label("Steps last frame:");
+                ui.monospace(format!("{}", stats.substeps));
+                ui.end_row();
+                ui.label("Step rate:");
+                ui.monospace(format!("{:.0} Hz", stats.step_rate));
+                ui.end_row();
+                ui.label("Time scale:");
+                ui.monospace(format!("{:.2}x", stats.time_scale));
+                ui.end_row();
+            });
+        });
+}
+
+// Altitude tape: size, the altitude range it spans, and tick spacing (display units)
+const ALTITUDE_TAPE_SIZE: egui::Vec2 = egui::vec2(70.0, 240.0);
+const ALTITUDE_TAPE_SPAN: f32 = 50.0;
+const ALTITUDE_TICK_SPACING: f32 = 5.0;
+const ALTITUDE_LABEL_SPACING: f32 = 10.0;
+// Attitude indicator radius (pixels)
+const ATTITUDE_INDICATOR_RADIUS: f32 = 40.0;
+
+// Instrument overlay along the left edge: an altitude tape with the descent rate, and
+// an attitude indicator with the horizon and the engine's gimbal deflection
+pub fn hud_overlay(
+    mut contexts: EguiContexts,
+    view_options: Res<ViewOptions>,
+    editor_state: Res<EditorState>,
+    lander_state: Res<LanderState>,
+    current_level: Res<CurrentLevel>,
+) {
+    if !view_options.show_hud {
+        return;
+    }
+    let (units, angle_units) = (editor_state.units, editor_state.angle_units);
+
+    let config = &current_level.config;
+    let up = config.physics.local_up(lander_state.position);
+    let agl = config.height_above_ground(lander_state.position) - LANDER_BASE_OFFSET;
+    let vertical_speed = lander_state.velocity.dot(up);
+    let roll = lander_state.rotation - (-up.x).atan2(up.y);
+    let roll = roll.sin().atan2(roll.cos());
+
+    egui::Area::new(egui::Id::new("hud"))
+        .anchor(egui::Align2::LEFT_CENTER, egui::vec2(10.0, 0.0))
+        .interactable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            draw_altitude_tape(ui, units.length(agl), units.length_unit());
+            let descent_color = if -vertical_speed > config.success.vy_max {
+                egui::Color32::RED
+            } else {
+                egui::Color32::WHITE
+            };
+            ui.colored_label(
+                descent_color,
+                format!(
+                    "VS {:+.1} {}",
+                    units.length(vertical_speed),
+                    units.speed_unit()
+                ),
+            );
+            ui.add_space(8.0);
+            draw_attitude_indicator(ui, roll, lander_state.gimbal_angle);
+            ui.label(format!(
+                "Roll {:+.2}{}",
+                angle_units.angle(roll),
+                angle_units.unit()
+            ));
+            if let ControlScheme::ThrustVector = config.control_scheme {
+                ui.label(format!(
+                    "Gimbal {:+.2}{}",
+                    angle_units.angle(lander_state.gimbal_angle),
+                    angle_units.unit()
+                ));
+            }
+        });
+}
+
+// Scrolling scale centered on the current height above ground, with the ground shaded
+fn draw_altitude_tape(ui: &mut egui::Ui, agl: f32, unit: &str) {
+    let (rect, _) = ui.allocate_exact_size(ALTITUDE_TAPE_SIZE, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, egui::Color32::from_black_alpha(160));
+
+    let pixels_per_meter = rect.height() / ALTITUDE_TAPE_SPAN;
+    let to_y = |altitude: f32| rect.center().y - (altitude - agl) * pixels_per_meter;
+
+    let ground_y = to_y(0.0);
+    if ground_y < rect.bottom() {
+        let ground = egui::Rect::from_min_max(
+            egui::pos2(rect.left(), ground_y.max(rect.top())),
+            rect.right_bottom(),
+        );
+        painter.rect_filled(ground, 0.0, egui::Color32::from_rgb(90, 60, 30));
+    }
+
+    let lowest = ((agl - ALTITUDE_TAPE_SPAN / 2.0) / ALTITUDE_TICK_SPACING).floor() as i32;
+    let highest = ((agl + ALTITUDE_TAPE_SPAN / 2.0) / ALTITUDE_TICK_SPACING).ceil() as i32;
+    for tick in lowest.max(0)..=highest {
+        let altitude = tick as f32 * ALTITUDE_TICK_SPACING;
+        let y = to_y(altitude);
+        let labelled = altitude % ALTITUDE_LABEL_SPACING == 0.0;
+        let length = if labelled { 12.0 } else { 6.0 };
+        painter.line_segment(
+            [
+                egui::pos2(rect.right() - length, y),
+                egui::pos2(rect.right(), y),
+            ],
+            egui::Stroke::new(1.0, egui::Color32::GRAY),
+        );
+        if labelled {
+            painter.text(
+                egui::pos2(rect.right() - 16.0, y),
+                egui::Align2::RIGHT_CENTER,
+                format!("{:.0}", altitude),
+                egui::FontId::monospace(11.0),
+                egui::Color32::GRAY,
+            );
+        }
+    }
+
+    // Current reading boxed at the center line
+    let readout = egui::Rect::from_center_size(rect.center(), egui::vec2(rect.width(), 18.0));
+    painter.rect_filled(readout, 2.0, egui::Color32::BLACK);
+    painter.rect_stroke(readout, 2.0, egui::Stroke::new(1.0, egui::Color32::WHITE));
+    painter.text(
+        readout.center(),
+        egui::Align2::CENTER_CENTER,
+        format!("{:.1} {}", agl, unit),
+        egui::FontId::monospace(12.0),
+        egui::Color32::WHITE,
+    );
+}
+
+// Body-fixed view: the horizon tilts against the roll and the plume line shows the gimbal
+fn draw_attitude_indicator(ui: &mut egui::Ui, roll: f32, gimbal: f32) {
+    let size = egui::Vec2::splat(2.0 * ATTITUDE_INDICATOR_RADIUS);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let center = rect.center();
+    let radius = ATTITUDE_INDICATOR_RADIUS;
+    painter.circle_filled(center, radius, egui::Color32::from_rgb(40, 80, 140));
+
+    // Ground half, from one end of the horizon round through straight down. Screen y
+    // points down, so the screen angle of the horizon is the roll itself.
+    let ground: Vec<egui::Pos2> = (0..=32)
+        .map(|i| {
+            let angle = roll + std::f32::consts::PI * i as f32 / 32.0;
+            center + radius * egui::vec2(angle.cos(), angle.sin())
+        })
+        .collect();
+    painter.add(egui::Shape::convex_polygon(
+        ground,
+        egui::Color32::from_rgb(110, 75, 40),
+        egui::Stroke::NONE,
+    ));
+    let horizon = radius * egui::vec2(roll.cos(), roll.sin());
+    painter.line_segment(
+        [center - horizon, center + horizon],
+        egui::Stroke::new(1.5, egui::Color32::WHITE),
+    );
+
+    // Fixed lander symbol with the engine plume along the gimbal angle
+    let half = 8.0;
+    painter.add(egui::Shape::closed_line(
+        vec![
+            center + egui::vec2(0.0, -half),
+            center + egui::vec2(-half * 0.7, half),
+            center + egui::vec2(half * 0.7, half),
+        ],
+        egui::Stroke::new(2.0, egui::Color32::YELLOW),
+    ));
+    let base = center + egui::vec2(0.0, half);
+    let plume = egui::vec2(gimbal.sin(), gimbal.cos()) * half * 1.5;
+    painter.line_segment(
+        [base, base + plume],
+        egui::Stroke::new(2.0, egui::Color32::from_rgb(255, 140, 0)),
+    );
+    painter.circle_stroke(center, radius, egui::Stroke::new(1.0, egui::Color32::GRAY));
+}
+
+// Name, unit, value and unit conversion of one telemetry plot
+type PlotSeries = (&'static str, &'static str, fn(&TelemetrySample) -> f32, f32);
+
+// Most points drawn per telemetry plot line
+const MAX_PLOT_POINTS: usize = 400;
+const PLOT_SIZE: egui::Vec2 = egui::vec2(320.0, 70.0);
+
+// Altitude, vertical speed, throttle and fuel over the current run, up to the time shown
+// (so review playback scrubs the plots too)
+pub fn telemetry_plot_window(
+    mut contexts: EguiContexts,
+    mut view_options: ResMut<ViewOptions>,
+    editor_state: Res<EditorState>,
+    telemetry: Res<TelemetryLog>,
+    lander_state: Res<LanderState>,
+) {
+    if !view_options.show_telemetry_plot {
+        return;
+    }
+
+    // The plots scale to fit, so only the readouts above them need converting
+    let units = editor_state.units;
+    let samples = telemetry.downsampled(lander_state.elapsed, MAX_PLOT_POINTS);
+    let series: [PlotSeries; 4] = [
+        (
+            "Altitude",
+            units.length_unit(),
+            |sample| sample.y,
+            units.length(1.0),
+        ),
+        (
+            "Vertical speed",
+            units.speed_unit(),
+            |sample| sample.vy,
+            units.length(1.0),
+        ),
+        ("Throttle", "%", |sample| sample.thrust * 100.0, 1.0),
+        (
+            "Fuel",
+            units.mass_unit(),
+            |sample| sample.fuel,
+            units.mass(1.0),
+        ),
+    ];
+
+    let mut open = true;
+    egui::Window::new("Telemetry Plots")
+        .open(&mut open)
+        .resizable(false)
+        .default_pos(egui::pos2(10.0, 200.0))
+        .show(contexts.ctx_mut(), |ui| {
+            for (name, unit, value, conversion) in series {
+                let current = samples.last().map(value).unwrap_or(0.0) * conversion;
+                ui.label(format!("{}: {:.1} {}", name, current, unit));
+                draw_plot(ui, &samples, value);
+            }
+        });
+    if !open {
+        view_options.show_telemetry_plot = false;
+    }
+}
+
+// One time-series line scaled to fit its own box, with a zero line when it's in range
+fn draw_plot(ui: &mut egui::Ui, samples: &[TelemetrySample], value: fn(&TelemetrySample) -> f32) {
+    let (rect, _) = ui.allocate_exact_size(PLOT_SIZE, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_stroke(rect, 2.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return;
+    };
+
+    let (min, max) = samples
+        .iter()
+        .map(value)
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), v| {
+            (min.min(v), max.max(v))
+        });
+    // Keep flat lines in the middle instead of dividing by zero
+    let (min, max) = if max - min < 1e-3 {
+        (min - 1.0, max + 1.0)
+    } else {
+        (min, max)
+    };
+    let duration = (last.t - first.t).max(1e-3);
+    let to_screen = |t: f32, v: f32| {
+        egui::pos2(
+            rect.left() + (t - first.t) / duration * rect.width(),
+            rect.bottom() - (v - min) / (max - min) * rect.height(),
+        )
+    };
+
+    if min < 0.0 && max > 0.0 {
+        painter.line_segment(
+            [to_screen(first.t, 0.0), to_screen(last.t, 0.0)],
+            egui::Stroke::new(1.0, egui::Color32::from_gray(70)),
+        );
+    }
+    let points = samples
+        .iter()
+        .map(|sample| to_screen(sample.t, value(sample)))
+        .collect();
+    painter.add(egui::Shape::line(
+        points,
+        egui::Stroke::new(1.5, egui::Color32::LIGHT_BLUE),
+    ));
+}
+
+// Touchdown-speed histogram buckets in the trial summary
+const HISTOGRAM_BINS: usize = 10;
+const HISTOGRAM_SIZE: egui::Vec2 = egui::vec2(320.0, 100.0);
+
+// Progress and results of the Monte Carlo batch
+pub fn monte_carlo_popup(
+    mut contexts: EguiContexts,
+    mut monte_carlo: ResMut<MonteCarlo>,
+    current_level: Res<CurrentLevel>,
+) {
+    if !monte_carlo.show {
+        return;
+    }
+
+    let mut open = true;
+    egui::Window::new("Monte Carlo Trials")
+        .open(&mut open)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(contexts.ctx_mut(), |ui| {
+            if let Some(error) = &monte_carlo.error {
+                ui.colored_label(egui::Color32::RED, error);
+                return;
+            }
+            if current_level.config.dispersion.is_none() {
+                ui.label("This level has no dispersion, so every trial starts the same.");
+            }
+
+            let total = monte_carlo.results.len();
+            if monte_carlo.running() {
+                ui.add(
+                    egui::ProgressBar::new(total as f32 / TRIAL_COUNT as f32)
+                        .text(format!("{} / {} trials", total, TRIAL_COUNT)),
+                );
+            }
+
+            let summary = monte_carlo.summary();
+            ui.heading(format!(
+                "Success rate: {:.0}%",
+                summary.success_rate(total) * 100.0
+            ));
+            ui.label(format!(
+                "Landed {}, crashed {}, timed out {}, script errors {}",
+                summary.landed, summary.crashed, summary.timed_out, summary.script_errors
+            ));
+            if let Some(fuel) = summary.mean_landed_fuel() {
+                ui.label(format!("Mean fuel left after landing: {:.1} kg", fuel));
+            }
+            if let Some((seed, error)) = monte_carlo.first_script_error() {
+                ui.colored_label(egui::Color32::RED, format!("Seed {}: {}", seed, error));
+            }
+
+            if let Some((mean, min, max)) = summary.touchdown_stats() {
+                ui.label(format!(
+                    "Touchdown speed: mean {:.2} m/s, min {:.2}, max {:.2}",
+                    mean, min, max
+                ));
+                draw_histogram(ui, &summary.touchdown_speeds, max);
+            }
         });
+    if !open {
+        monte_carlo.show = false;
+    }
+}
+
+// Bar chart of how many values fall in each of HISTOGRAM_BINS buckets from 0 to `max`
+fn draw_histogram(ui: &mut egui::Ui, values: &[f32], max: f32) {
+    let (rect, _) = ui.allocate_exact_size(HISTOGRAM_SIZE, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_stroke(rect, 2.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+
+    let bin_width = max.max(1e-3) / HISTOGRAM_BINS as f32;
+    let mut counts = [0usize; HISTOGRAM_BINS];
+    for value in values {
+        let bin = ((value / bin_width) as usize).min(HISTOGRAM_BINS - 1);
+        counts[bin] += 1;
+    }
+    let tallest = counts.iter().copied().max().unwrap_or(0).max(1) as f32;
+
+    let bar_width = rect.width() / HISTOGRAM_BINS as f32;
+    for (i, count) in counts.iter().enumerate() {
+        let height = *count as f32 / tallest * rect.height();
+        let left = rect.left() + i as f32 * bar_width;
+        painter.rect_filled(
+            egui::Rect::from_min_max(
+                egui::pos2(left + 1.0, rect.bottom() - height),
+                egui::pos2(left + bar_width - 1.0, rect.bottom()),
+            ),
+            0.0,
+            egui::Color32::LIGHT_BLUE,
+        );
+    }
+    ui.horizontal(|ui| {
+        ui.small("0 m/s");
+        ui.add_space(HISTOGRAM_SIZE.x - 90.0);
+        ui.small(format!("{:.2} m/s", max));
     });
 }
 
+// Live checklist of the success criteria on guided levels, each greening out while it holds
+pub fn success_checklist_overlay(
+    mut contexts: EguiContexts,
+    current_level: Res<CurrentLevel>,
+    lander_state: Res<LanderState>,
+) {
+    let config = &current_level.config;
+    if !config.guided {
+        return;
+    }
+
+    let success = &config.success;
+    let checks = &lander_state.success_checks;
+    let position_label = match (&success.objective, &success.position_box.reference) {
+        (
+            Objective::Ascent {
+                target_altitude, ..
+            },
+            _,
+        ) => {
+            format!("Altitude {:.0} m", target_altitude)
+        }
+        (Objective::Landing, Reference::Absolute) => "Down in the landing zone".to_string(),
+        (Objective::Landing, Reference::Initial) => "Inside the hover box".to_string(),
+    };
+    let mut items = vec![
+        (position_label, checks.position),
+        (
+            format!("Vertical speed < {:.1} m/s", success.vy_max),
+            checks.vertical_speed,
+        ),
+        (
+            format!("Horizontal speed < {:.1} m/s", success.vx_max),
+            checks.horizontal_speed,
+        ),
+        (
+            format!(
+                "Attitude within {:.0}°",
+                success.angle_tolerance.to_degrees()
+            ),
+            checks.attitude,
+        ),
+    ];
+    if let Some(max_rate) = success.max_angular_rate {
+        items.push((
+            format!("Rotation rate < {:.2} rad/s", max_rate),
+            checks.rate,
+        ));
+    }
+    if let Some(max_angle) = success.max_velocity_angle {
+        items.push((
+            format!(
+                "Touchdown within {:.0}° of vertical",
+                max_angle.to_degrees()
+            ),
+            checks.descent,
+        ));
+    }
+    if !config.waypoints.is_empty() {
+        items.insert(
+            0,
+            (
+                format!(
+                    "Waypoints {}/{}",
+                    lander_state.waypoint_index,
+                    config.waypoints.len()
+                ),
+                checks.waypoints,
+            ),
+        );
+    }
+
+    egui::Window::new("Landing Checklist")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(
+            egui::Align2::RIGHT_TOP,
+            egui::vec2(-RIGHT_PANEL_WIDTH - 10.0, 40.0),
+        )
+        .show(contexts.ctx_mut(), |ui| {
+            for (label, ok) in items {
+                if ok {
+                    ui.colored_label(egui::Color32::GREEN, format!("✔ {}", label));
+                } else {
+                    ui.colored_label(egui::Color32::GRAY, format!("○ {}", label));
+                }
+            }
+            ui.separator();
+            if lander_state.landed {
+                ui.colored_label(egui::Color32::GREEN, "All criteria held. Landed!");
+            } else {
+                ui.label(format!(
+                    "Hold all for {:.1} s: {:.1} s",
+                    success.persistence_period, lander_state.success_timer
+                ));
+            }
+        });
+}
+
+// Lists the manual control keys for the current level's control scheme, toggled with F1
+pub fn control_hints_overlay(
+    mut contexts: EguiContexts,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut view_options: ResMut<ViewOptions>,
+    current_level: Res<CurrentLevel>,
+) {
+    if keys.just_pressed(manual::TOGGLE_HINTS_KEY) {
+        view_options.show_control_hints = !view_options.show_control_hints;
+    }
+    if !view_options.show_control_hints {
+        return;
+    }
+
+    egui::Window::new("Manual Controls")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(10.0, -10.0))
+        .show(contexts.ctx_mut(), |ui| {
+            egui::Grid::new("control_hints")
+                .num_columns(2)
+                .spacing([16.0, 4.0])
+                .show(ui, |ui| {
+                    for (keys, action) in
+                        manual::keybinding_hints(&current_level.config.control_scheme)
+                    {
+                        ui.strong(keys);
+                        ui.label(action);
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+// Counts down before a run starts, with the remaining seconds shown over the scene
+pub fn countdown_overlay(
+    mut contexts: EguiContexts,
+    time: Res<Time>,
+    mut editor_state: ResMut<EditorState>,
+) {
+    if editor_state.simulation_state != SimulationState::Countdown {
+        return;
+    }
+
+    editor_state.countdown_remaining -= time.delta_secs();
+    if editor_state.countdown_remaining <= 0.0 {
+        editor_state.simulation_state = SimulationState::Running;
+        return;
+    }
+
+    // Center over the simulation view, left of the code panel
+    egui::Area::new(egui::Id::new("countdown"))
+        .anchor(
+            egui::Align2::CENTER_CENTER,
+            egui::vec2(-RIGHT_PANEL_WIDTH / 2.0, 0.0),
+        )
+        .interactable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(
+                egui::RichText::new(format!("{}", editor_state.countdown_remaining.ceil()))
+                    .size(96.0)
+                    .strong()
+                    .color(egui::Color32::WHITE),
+            );
+        });
+}
+
+// How far past the edge of the view the lander may go before the auto-pause (pixels)
+const OFFSCREEN_MARGIN: f32 = 100.0;
+
+// Center of the level's success region in world coordinates
+fn target_center(config: &LevelConfig) -> Vec2 {
+    let position_box = &config.success.position_box;
+    let box_center = Vec2::new(
+        (position_box.x_min + position_box.x_max) / 2.0,
+        (position_box.y_min + position_box.y_max) / 2.0,
+    );
+    match (&config.success.objective, &position_box.reference) {
+        (
+            Objective::Ascent {
+                target_altitude, ..
+            },
+            _,
+        ) => Vec2::new(box_center.x, *target_altitude),
+        (Objective::Landing, Reference::Initial) => {
+            box_center + Vec2::new(config.initial.x0, config.initial.y0)
+        }
+        (Objective::Landing, Reference::Absolute) => box_center,
+    }
+}
+
+// Pause the run when the lander leaves a view framed on the target by more than
+// OFFSCREEN_MARGIN, so a diverging controller doesn't keep flying unseen until it
+// trips the out-of-bounds check. Only the moment of leaving pauses, so resuming
+// while still off-screen lets the run continue.
+pub fn auto_pause_offscreen(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut editor_state: ResMut<EditorState>,
+    lander_state: Res<LanderState>,
+    current_level: Res<CurrentLevel>,
+    camera_state: Res<CameraState>,
+    mut was_offscreen: Local<bool>,
+) {
+    if !editor_state.auto_pause_offscreen
+        || editor_state.simulation_state != SimulationState::Running
+        || lander_state.crashed
+        || lander_state.landed
+    {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    // The simulation view is the window minus the code panel
+    let half_view = Vec2::new(
+        (window.width() - RIGHT_PANEL_WIDTH).max(0.0) / 2.0,
+        window.height() / 2.0,
+    );
+    let screen_delta =
+        (lander_state.position - target_center(&current_level.config)) * camera_state.scale;
+    let offscreen = screen_delta.x.abs() > half_view.x + OFFSCREEN_MARGIN
+        || screen_delta.y.abs() > half_view.y + OFFSCREEN_MARGIN;
+
+    if offscreen && !*was_offscreen {
+        editor_state.simulation_state = SimulationState::Paused;
+        editor_state.offscreen_paused = true;
+    }
+    *was_offscreen = offscreen;
+}
+
 pub fn level_complete_popup(
     mut contexts: EguiContexts,
     mut popup: ResMut<LevelCompletePopup>,
     mut editor_state: ResMut<EditorState>,
     mut state: ResMut<NextState<GameState>>,
+    progress: Res<Persistent<LevelProgress>>,
+    touchdown: Res<TouchdownReport>,
+    current_level: Res<CurrentLevel>,
 ) {
     if popup.show {
         editor_state.simulation_state = SimulationState::Paused;
+        let success = &current_level.config.success;
+        let half_width = (success.position_box.x_max - success.position_box.x_min) / 2.0;
 
         egui::Window::new("Level Complete!")
             .collapsible(false)
@@ -538,6 +2553,50 @@ pub fn level_complete_popup(
             .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
             .show(contexts.ctx_mut(), |ui| {
                 ui.label("Congratulations! You've completed this level!");
+                ui.add_space(4.0);
+                for (label, value, unit, limit) in [
+                    (
+                        "Vertical speed",
+                        touchdown.vertical_speed,
+                        "m/s",
+                        success.vy_max,
+                    ),
+                    (
+                        "Horizontal speed",
+                        touchdown.horizontal_speed,
+                        "m/s",
+                        success.vx_max,
+                    ),
+                    (
+                        "Tilt",
+                        touchdown.tilt.to_degrees(),
+                        "°",
+                        success.angle_tolerance.to_degrees(),
+                    ),
+                    ("Offset from target", touchdown.offset, "m", half_width),
+                ] {
+                    ui.colored_label(
+                        margin_color(value, limit),
+                        format!(
+                            "{}: {:.2} {} (limit {})",
+                            label,
+                            value,
+                            unit,
+                            format_limit(limit)
+                        ),
+                    );
+                }
+                ui.label(format!(
+                    "Fuel remaining: {:.1} kg",
+                    touchdown.fuel_remaining
+                ));
+                ui.add_space(4.0);
+                if let Some(best) = persistence::get_best_score(popup.completed_level, &progress) {
+                    ui.label(format!(
+                        "Best run: {:.1} kg fuel left, {:.1} s, touchdown at {:.2} m/s",
+                        best.fuel_remaining, best.time, best.touchdown_speed
+                    ));
+                }
                 ui.add_space(8.0);
 
                 ui.horizontal(|ui| {
@@ -553,12 +2612,34 @@ pub fn level_complete_popup(
     }
 }
 
+// Value inside the worse half of its allowance (or past it) gets flagged
+const TIGHT_MARGIN: f32 = 0.5;
+
+// Green for a comfortable margin to the limit, yellow for a close call
+fn margin_color(value: f32, limit: f32) -> egui::Color32 {
+    if value <= limit * TIGHT_MARGIN {
+        egui::Color32::GREEN
+    } else {
+        egui::Color32::YELLOW
+    }
+}
+
+// Levels that don't care about a criterion set its limit absurdly high
+fn format_limit(limit: f32) -> String {
+    if limit >= 1.0e4 {
+        "none".to_string()
+    } else {
+        format!("{:.2}", limit)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn handle_escape(
     keys: Res<ButtonInput<KeyCode>>,
     mut state: ResMut<NextState<GameState>>,
     mut about_popup: ResMut<AboutPopupState>,
     editor_state: Res<EditorState>,
-    progress: ResMut<Persistent<LevelProgress>>,
+    mut progress: ResMut<Persistent<LevelProgress>>,
     current_level: Res<CurrentLevel>,
     level_manager: Res<LevelManager>,
     mut popup: ResMut<LevelCompletePopup>,
@@ -572,7 +2653,12 @@ pub(crate) fn handle_escape(
             .find(|(_, name)| name == &current_level.config.name)
         {
             // Save current editor state before switching
-            let _ = persistence::save_editor_state(*level_num, editor_state.code.clone(), progress);
+            let _ = persistence::save_editor_state(
+                *level_num,
+                editor_state.code.clone(),
+                editor_state.notes.clone(),
+                &mut progress,
+            );
         }
         popup.show = false;
         state.set(GameState::LevelSelect);
@@ -626,19 +2712,101 @@ pub fn about_popup(
 }
 
 pub fn export_code(code: &str, level_num: usize) {
-    let filename = format!("level{}_solution.rhai", level_num);
+    save_text_file(
+        &format!("level{}_solution.rhai", level_num),
+        code,
+        "text/plain",
+    );
+}
+
+thread_local! {
+    // Contents of the last picked script file, or why it couldn't be read. The browser
+    // reads files asynchronously, so the editor picks this up on a later frame.
+    static IMPORTED_CODE: std::cell::RefCell<Option<Result<String, String>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+fn take_imported_code() -> Option<Result<String, String>> {
+    IMPORTED_CODE.with(|imported| imported.borrow_mut().take())
+}
 
+fn set_imported_code(imported: Result<String, String>) {
+    IMPORTED_CODE.with(|slot| *slot.borrow_mut() = Some(imported));
+}
+
+// Let the user pick a .rhai file: a file input in the browser, an open dialog natively
+pub fn import_code() {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use wasm_bindgen::closure::Closure;
+        use wasm_bindgen::JsCast;
+        use web_sys::{FileReader, HtmlInputElement};
+
+        let document = web_sys::window()
+            .expect("Failed to get window")
+            .document()
+            .expect("Failed to get document");
+
+        let input = document
+            .create_element("input")
+            .expect("Failed to create input")
+            .dyn_into::<HtmlInputElement>()
+            .expect("Failed to convert to input");
+        input.set_type("file");
+        input.set_accept(".rhai,text/plain");
+
+        // Once a file is picked, read it as text and hand it to the editor
+        let picker = input.clone();
+        let on_change = Closure::once(move || {
+            let Some(file) = picker.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            let Ok(reader) = FileReader::new() else {
+                set_imported_code(Err("The browser can't read files".into()));
+                return;
+            };
+            let loaded = reader.clone();
+            let on_load = Closure::once(move || {
+                match loaded.result().ok().and_then(|result| result.as_string()) {
+                    Some(code) => set_imported_code(Ok(code)),
+                    None => set_imported_code(Err("The file isn't text".into())),
+                }
+            });
+            reader.set_onload(Some(on_load.as_ref().unchecked_ref()));
+            on_load.forget();
+            if reader.read_as_text(&file).is_err() {
+                set_imported_code(Err("Failed to read the file".into()));
+            }
+        });
+        input.set_onchange(Some(on_change.as_ref().unchecked_ref()));
+        on_change.forget();
+        input.click();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if let Some(path) = FileDialog::new()
+            .add_filter("Rhai script", &["rhai"])
+            .pick_file()
+        {
+            set_imported_code(std::fs::read_to_string(path).map_err(|e| e.to_string()));
+        }
+    }
+}
+
+// Save text under a suggested file name: a download in the browser, a save dialog natively
+fn save_text_file(filename: &str, contents: &str, mime_type: &str) {
     #[cfg(target_arch = "wasm32")]
     {
         use js_sys::Array;
         use wasm_bindgen::JsCast;
         use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
 
-        // Create a Blob containing the code
+        // Create a Blob containing the text
         let properties = BlobPropertyBag::new();
-        properties.set_type("text/plain");
+        properties.set_type(mime_type);
         let blob_parts = Array::new();
-        blob_parts.push(&js_sys::JsString::from(code));
+        blob_parts.push(&js_sys::JsString::from(contents));
         let blob = Blob::new_with_str_sequence_and_options(&blob_parts, &properties)
             .expect("Failed to create blob");
 
@@ -658,7 +2826,7 @@ pub fn export_code(code: &str, level_num: usize) {
             .expect("Failed to convert to anchor");
 
         anchor.set_href(&url);
-        anchor.set_download(&filename);
+        anchor.set_download(filename);
         anchor.click();
 
         // Clean up
@@ -667,8 +2835,9 @@ pub fn export_code(code: &str, level_num: usize) {
 
     #[cfg(not(target_arch = "wasm32"))]
     {
-        if let Some(path) = FileDialog::new().set_file_name(&filename).save_file() {
-            std::fs::write(path, code).expect("Failed to write file");
+        let _ = mime_type; // Only the browser download needs the content type
+        if let Some(path) = FileDialog::new().set_file_name(filename).save_file() {
+            std::fs::write(path, contents).expect("Failed to write file");
         }
     }
 }
@@ -702,6 +2871,196 @@ pub fn handle_script_loading(
     }
 }
 
+// Scrubber for stepping through the recorded trajectory of the last run
+pub fn trajectory_review_ui(
+    mut contexts: EguiContexts,
+    mut playback: ResMut<TrajectoryPlayback>,
+    mut lander_state: ResMut<LanderState>,
+    trajectory: Res<Trajectory>,
+) {
+    if !playback.active || trajectory.samples.is_empty() {
+        return;
+    }
+
+    let last_index = trajectory.samples.len() - 1;
+    let current_t = trajectory.samples[playback.index.min(last_index)].t;
+
+    egui::Window::new("Run Review")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::LEFT_TOP, egui::Vec2::new(10.0, 40.0))
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(format!(
+                "t = {:.2} s / {:.2} s",
+                current_t, trajectory.samples[last_index].t
+            ));
+
+            let mut index = playback.index;
+            if ui
+                .add(egui::Slider::new(&mut index, 0..=last_index).show_value(false))
+                .changed()
+            {
+                playback.reversing = false;
+                playback.playing = false;
+                playback.seek(index, &mut lander_state, &trajectory);
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("< Step").clicked() {
+                    playback.reversing = false;
+                    playback.playing = false;
+                    let index = playback.index.saturating_sub(1);
+                    playback.seek(index, &mut lander_state, &trajectory);
+                }
+
+                let rewind_text = if playback.reversing { "Stop" } else { "Rewind" };
+                if ui.button(rewind_text).clicked() {
+                    playback.reversing = !playback.reversing;
+                    playback.playing = false;
+                }
+
+                let play_text = if playback.playing { "Pause" } else { "Play" };
+                if ui.button(play_text).clicked() {
+                    // Playing from the end starts the replay over
+                    if !playback.playing && playback.index >= last_index {
+                        playback.seek(0, &mut lander_state, &trajectory);
+                    }
+                    playback.playing = !playback.playing;
+                    playback.reversing = false;
+                }
+
+                if ui.button("Step >").clicked() {
+                    playback.reversing = false;
+                    playback.playing = false;
+                    let index = (playback.index + 1).min(last_index);
+                    playback.seek(index, &mut lander_state, &trajectory);
+                }
+            });
+        });
+}
+
+// Share or load a randomized scenario so a class can all fly the same one
+#[allow(clippy::too_many_arguments)]
+pub fn challenge_popup(
+    mut contexts: EguiContexts,
+    mut popup: ResMut<ChallengePopupState>,
+    mut editor_state: ResMut<EditorState>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut script_engine: ResMut<ScriptEngine>,
+    mut lander_state: ResMut<LanderState>,
+    mut camera_state: ResMut<CameraState>,
+    (mut trajectory, mut playback): (ResMut<Trajectory>, ResMut<TrajectoryPlayback>),
+    (mut reset_flag, mut reset_vis): (ResMut<ResetVisibilityFlag>, ResMut<ResetVisualization>),
+    level_manager: Res<LevelManager>,
+    progress: Res<Persistent<LevelProgress>>,
+    asset_server: Res<AssetServer>,
+    script_assets: Res<Assets<ScriptAsset>>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    if !popup.show {
+        return;
+    }
+
+    let current_number = level_manager
+        .available_levels
+        .iter()
+        .find(|(_, name)| name == &current_level.config.name)
+        .map(|(number, _)| *number);
+
+    let mut generate = false;
+    let mut load = false;
+    egui::Window::new("Challenge Code")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label("Everyone who loads the same code flies the same randomized scenario.");
+            ui.add_space(8.0);
+            ui.add(egui::TextEdit::singleline(&mut popup.code).desired_width(320.0));
+            ui.horizontal(|ui| {
+                generate = ui
+                    .add_enabled(
+                        current_number.is_some(),
+                        egui::Button::new("Generate Challenge Code"),
+                    )
+                    .clicked();
+                load = ui.button("Load Challenge Code").clicked();
+                if ui.button("Close").clicked() || keys.just_pressed(KeyCode::Escape) {
+                    popup.show = false;
+                }
+            });
+            if let Some(message) = &popup.message {
+                ui.label(message);
+            }
+        });
+
+    if generate {
+        if let Some(level) = current_number {
+            popup.code = challenge::encode(&Challenge {
+                level,
+                seed: lander_state.seed,
+                dispersion: lander_state.dispersion,
+            });
+            contexts
+                .ctx_mut()
+                .output_mut(|output| output.copied_text = popup.code.clone());
+            popup.message = Some("Copied to the clipboard.".into());
+        }
+    }
+
+    if load {
+        let challenge = match challenge::decode(&popup.code) {
+            Ok(challenge) => challenge,
+            Err(error) => {
+                popup.message = Some(error);
+                return;
+            }
+        };
+        if !persistence::is_level_available(challenge.level, &level_manager, &progress) {
+            popup.message = Some(format!("Level {} is still locked.", challenge.level));
+            return;
+        }
+
+        if current_number != Some(challenge.level) {
+            let Some(new_config) = level_manager.get_level(challenge.level) else {
+                popup.message = Some(format!("There is no level {}.", challenge.level));
+                return;
+            };
+            switch_level(
+                challenge.level,
+                new_config,
+                &level_manager,
+                &mut editor_state,
+                &mut current_level,
+                &mut script_engine,
+                &progress,
+                &asset_server,
+                &script_assets,
+            );
+            reset_vis.0 = true;
+        }
+
+        editor_state.simulation_state = SimulationState::Stopped;
+        script_engine.error_message = None;
+        script_engine.clear_user_state();
+        lander_state.seed = challenge.seed;
+        lander_state.dispersion = challenge.dispersion;
+        playback.cancel();
+        reset_simulation(
+            &mut lander_state,
+            &current_level,
+            &mut camera_state,
+            &mut trajectory,
+            &mut script_engine,
+        );
+        reset_flag.0 = true;
+        popup.message = Some(format!(
+            "Loaded challenge for level {}: {}",
+            challenge.level, current_level.config.name
+        ));
+    }
+}
+
 pub fn hint_popup(
     mut contexts: EguiContexts,
     mut popup: ResMut<HintPopupState>,
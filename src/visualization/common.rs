@@ -1,16 +1,22 @@
 use bevy::prelude::*;
 
 // Constants moved from original visualization.rs
-pub const WORLD_TO_SCREEN_SCALE: f32 = 10.0;
+pub const WORLD_TO_SCREEN_SCALE: f32 = 10.0; // Pixels per meter at the default zoom
 pub const RIGHT_PANEL_WIDTH: f32 = 600.0;
 pub const GROUND_OFFSET: f32 = -200.0;
 pub const MIN_VIEW_HEIGHT: f32 = 30.0;
 
+// Zoom limits in pixels per meter
+pub const MIN_WORLD_TO_SCREEN_SCALE: f32 = 0.5;
+pub const MAX_WORLD_TO_SCREEN_SCALE: f32 = 50.0;
+
 #[derive(Resource)]
 pub struct CameraState {
-    pub following: bool,
+    pub following: bool, // Track the lander; false while the user has panned the view
     pub target_offset: Vec2,
     pub explosion_spawned: bool,
+    pub lead: Vec2, // Smoothed look-ahead in meters added to the follow target
+    pub scale: f32, // Pixels per meter, changed by zooming
 }
 
 impl Default for CameraState {
@@ -19,19 +25,90 @@ impl Default for CameraState {
             following: true,
             target_offset: Vec2::ZERO,
             explosion_spawned: false,
+            lead: Vec2::ZERO,
+            scale: WORLD_TO_SCREEN_SCALE,
         }
     }
 }
 
+impl CameraState {
+    // Size multiplier for things built at the default zoom
+    pub fn zoom(&self) -> f32 {
+        self.scale / WORLD_TO_SCREEN_SCALE
+    }
+
+    // Change the scale keeping the world point under `anchor` (screen space) in place
+    pub fn zoom_about(&mut self, factor: f32, anchor: Vec2) {
+        let scale =
+            (self.scale * factor).clamp(MIN_WORLD_TO_SCREEN_SCALE, MAX_WORLD_TO_SCREEN_SCALE);
+        let world = screen_to_world(anchor, self);
+        self.scale = scale;
+        self.target_offset += world_to_screen(world, self) - anchor;
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct ResetVisualization(pub bool);
 
+// Optional overlays toggled from the View menu
+#[derive(Resource)]
+pub struct ViewOptions {
+    pub show_forces: bool, // Free-body diagram of gravity, thrust and net force
+    pub show_control_hints: bool, // Manual control keybindings for the current level
+    pub show_heatmap: bool, // Density of all recorded attempts on the level
+    pub camera_lead: f32,  // Seconds of velocity look-ahead for the camera (0 = centered)
+    pub show_sim_stats: bool, // Integration timestep and step rate debug panel
+    pub show_hud: bool,    // Altitude tape and attitude indicator along the left edge
+    pub show_trail: bool,  // Fading trail of the lander's recent path
+    pub show_ghost: bool,  // Replay of the level's best run alongside the current one
+    pub show_telemetry_plot: bool, // Time-series plots of the current run
+    pub show_motion_vectors: bool, // Velocity and thrust arrows on the lander
+    pub show_range_axes: bool, // Down-range and cross-range lines through the landing target
+    pub motion_vector_gain: f32, // Length multiplier for the velocity and thrust arrows
+    pub telemetry_decimals: usize, // Decimal places in the telemetry readouts
+    pub scientific_threshold: f32, // Magnitude above which telemetry switches to scientific notation
+}
+
+impl Default for ViewOptions {
+    fn default() -> Self {
+        Self {
+            show_forces: false,
+            show_control_hints: false,
+            show_heatmap: false,
+            camera_lead: 0.0,
+            show_sim_stats: false,
+            show_hud: false,
+            show_trail: true,
+            show_ghost: true,
+            show_telemetry_plot: false,
+            show_motion_vectors: false,
+            show_range_axes: false,
+            motion_vector_gain: 1.0,
+            telemetry_decimals: 1,
+            scientific_threshold: 1.0e5,
+        }
+    }
+}
+
+// Set when the attempt history changed and the heatmap needs rebuilding
+#[derive(Resource, Default)]
+pub struct HeatmapDirty(pub bool);
+
 // Common utility functions
-pub fn world_to_screen(pos: Vec2, camera_offset: Vec2) -> Vec2 {
+pub fn world_to_screen(pos: Vec2, camera: &CameraState) -> Vec2 {
+    let center_offset = -(RIGHT_PANEL_WIDTH / 2.0);
+
+    Vec2::new(
+        pos.x * camera.scale + center_offset - camera.target_offset.x,
+        pos.y * camera.scale + GROUND_OFFSET - camera.target_offset.y,
+    )
+}
+
+pub fn screen_to_world(screen: Vec2, camera: &CameraState) -> Vec2 {
     let center_offset = -(RIGHT_PANEL_WIDTH / 2.0);
 
     Vec2::new(
-        pos.x * WORLD_TO_SCREEN_SCALE + center_offset - camera_offset.x,
-        pos.y * WORLD_TO_SCREEN_SCALE + GROUND_OFFSET - camera_offset.y,
+        (screen.x - center_offset + camera.target_offset.x) / camera.scale,
+        (screen.y - GROUND_OFFSET + camera.target_offset.y) / camera.scale,
     )
 }
@@ -7,7 +7,7 @@ use crate::levels::CurrentLevel;
 use bevy::prelude::*;
 
 // Re-export the main types that other modules need
-pub use common::{CameraState, ResetVisualization};
+pub use common::{CameraState, HeatmapDirty, ResetVisualization, ViewOptions};
 pub use viz_2d::components::{MainCamera, ResetVisibilityFlag};
 
 #[derive(Component)]
@@ -23,14 +23,27 @@ pub struct VisualizationPlugin;
 impl Plugin for VisualizationPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<viz_2d::particles::ParticleSpawnTimer>()
+            .init_resource::<viz_2d::trail::TrajectoryTrail>()
             .add_systems(
                 Update,
                 (
-                    viz_2d::systems::update_visualization,
+                    (
+                        viz_2d::camera::camera_controls,
+                        viz_2d::systems::update_visualization,
+                    )
+                        .chain(),
                     viz_2d::systems::update_grid_lines,
                     viz_2d::systems::reset_lander_visibility,
                     viz_2d::systems::reset_visualization_system,
                     viz_2d::particles::particle_system,
+                    viz_2d::systems::draw_force_vectors,
+                    viz_2d::systems::draw_motion_vectors,
+                    viz_2d::systems::draw_waypoints,
+                    viz_2d::systems::draw_range_axes,
+                    viz_2d::heatmap::update_heatmap,
+                    viz_2d::staging::update_jettisoned_stage,
+                    (viz_2d::trail::record_trail, viz_2d::trail::draw_trail).chain(),
+                    viz_2d::ghost::draw_ghost,
                 ),
             );
     }
@@ -42,7 +55,8 @@ pub fn spawn_visualization(
     meshes: ResMut<Assets<Mesh>>,
     materials: ResMut<Assets<ColorMaterial>>,
     level: Res<CurrentLevel>,
+    camera_state: ResMut<CameraState>,
 ) {
     // For now, always use 2D visualization
-    viz_2d::systems::spawn_visualization(commands, meshes, materials, level);
+    viz_2d::systems::spawn_visualization(commands, meshes, materials, level, camera_state);
 }
@@ -0,0 +1,57 @@
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::EguiContexts;
+
+use crate::visualization::common::*;
+
+// Zoom factor per scroll-wheel notch
+const ZOOM_STEP: f32 = 1.1;
+// Pixel-unit scroll deltas (touchpads, the web) per wheel notch
+const PIXELS_PER_SCROLL_LINE: f32 = 50.0;
+
+// Scroll-wheel zoom about the cursor and middle-mouse drag to pan. Panning stops the
+// camera following the lander until it's recentered from the View menu or by a reset.
+pub fn camera_controls(
+    mut contexts: EguiContexts,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut wheel: EventReader<MouseWheel>,
+    mut motion: EventReader<MouseMotion>,
+    mut camera_state: ResMut<CameraState>,
+) {
+    // Leave the scroll and drag to the editor and windows when the pointer is over them
+    if contexts.ctx_mut().wants_pointer_input() || contexts.ctx_mut().is_pointer_over_area() {
+        wheel.clear();
+        motion.clear();
+        return;
+    }
+
+    let notches: f32 = wheel
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_SCROLL_LINE,
+        })
+        .sum();
+    if notches != 0.0 {
+        let anchor = windows
+            .get_single()
+            .ok()
+            .and_then(|window| {
+                let cursor = window.cursor_position()?;
+                Some(Vec2::new(
+                    cursor.x - window.width() / 2.0,
+                    window.height() / 2.0 - cursor.y,
+                ))
+            })
+            .unwrap_or(Vec2::new(-(RIGHT_PANEL_WIDTH / 2.0), 0.0));
+        camera_state.zoom_about(ZOOM_STEP.powf(notches), anchor);
+    }
+
+    let drag: Vec2 = motion.read().map(|event| event.delta).sum();
+    if buttons.pressed(MouseButton::Middle) && drag != Vec2::ZERO {
+        camera_state.following = false;
+        camera_state.target_offset += Vec2::new(-drag.x, drag.y);
+    }
+}
@@ -12,6 +12,15 @@ pub struct Ground;
 #[derive(Component)]
 pub struct TargetZone;
 
+#[derive(Component)]
+pub struct Obstacle(pub Vec2); // No-fly box, centered here in world coordinates (meters)
+
+#[derive(Component)]
+pub struct TerrainMesh; // Filled ground profile, drawn in world meters from the origin
+
+#[derive(Component)]
+pub struct TargetAltitude(pub f32); // Altitude line for ascent objectives (meters)
+
 #[derive(Component)]
 pub struct GridSystem;
 
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use bevy_persistent::prelude::*;
+
+use crate::constants::{LANDER_HEIGHT, LANDER_WIDTH};
+use crate::levels::{CurrentLevel, LevelManager};
+use crate::persistence::{GhostSample, LevelProgress};
+use crate::simulation::LanderState;
+use crate::visualization::common::*;
+
+// Ghost state at time `t`, interpolated between the saved samples and held at the
+// first and last one outside the recording
+fn sample_at(ghost: &[GhostSample], t: f32) -> Option<GhostSample> {
+    let next = ghost.partition_point(|sample| sample.t <= t);
+    if next == 0 {
+        return ghost.first().copied();
+    }
+    let Some(b) = ghost.get(next) else {
+        return ghost.last().copied();
+    };
+    let a = ghost[next - 1];
+    let f = ((t - a.t) / (b.t - a.t).max(f32::EPSILON)).clamp(0.0, 1.0);
+    Some(GhostSample {
+        t,
+        x: a.x + (b.x - a.x) * f,
+        y: a.y + (b.y - a.y) * f,
+        rotation: a.rotation + (b.rotation - a.rotation) * f,
+        thrust: a.thrust + (b.thrust - a.thrust) * f,
+    })
+}
+
+// Faint outline of the lander flying the level's best run, kept in step with the
+// current run's clock (including while reviewing a run)
+pub fn draw_ghost(
+    mut gizmos: Gizmos,
+    view_options: Res<ViewOptions>,
+    camera_state: Res<CameraState>,
+    lander_state: Res<LanderState>,
+    progress: Res<Persistent<LevelProgress>>,
+    level: Res<CurrentLevel>,
+    level_manager: Res<LevelManager>,
+) {
+    if !view_options.show_ghost {
+        return;
+    }
+
+    let Some(&(level_num, _)) = level_manager
+        .available_levels
+        .iter()
+        .find(|(_, name)| name == &level.config.name)
+    else {
+        return;
+    };
+    let Some(best) = progress.best_scores.get(&level_num) else {
+        return;
+    };
+    let Some(sample) = sample_at(&best.ghost, lander_state.elapsed) else {
+        return;
+    };
+
+    let center = world_to_screen(Vec2::new(sample.x, sample.y), &camera_state);
+    let rotation = Rot2::radians(sample.rotation);
+    let half_height = LANDER_HEIGHT / 2.0 * camera_state.scale;
+    let half_width = LANDER_WIDTH / 2.0 * camera_state.scale;
+
+    // Same triangle as the lander mesh
+    let corners = [
+        Vec2::new(0.0, half_height),
+        Vec2::new(-half_width, -half_height),
+        Vec2::new(half_width, -half_height),
+    ]
+    .map(|corner| center + rotation * corner);
+    gizmos.linestrip_2d(
+        [corners[0], corners[1], corners[2], corners[0]],
+        Color::srgba(1.0, 1.0, 1.0, 0.35),
+    );
+
+    // Plume below the base, as long as the ghost's throttle
+    if sample.thrust > 0.0 {
+        let base = center + rotation * Vec2::new(0.0, -half_height);
+        let plume = rotation * Vec2::new(0.0, -half_height * 2.0 * sample.thrust);
+        gizmos.line_2d(base, base + plume, Color::srgba(1.0, 0.6, 0.2, 0.35));
+    }
+}
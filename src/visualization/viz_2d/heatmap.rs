@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_persistent::prelude::*;
+
+use crate::levels::{CurrentLevel, LevelManager};
+use crate::persistence::{self, AttemptHistory};
+use crate::visualization::common::*;
+
+const HEATMAP_CELL_SIZE: f32 = 5.0; // meters
+
+#[derive(Component)]
+pub struct HeatmapCell {
+    pub world_pos: Vec2, // Cell center in world coordinates
+}
+
+// Count how many attempts passed through each cell (once per attempt, so slow
+// hovering doesn't dominate) and where the crashed attempts ended
+fn accumulate(attempts: &[persistence::Attempt]) -> (HashMap<IVec2, u32>, HashMap<IVec2, u32>) {
+    let to_cell = |(x, y): (f32, f32)| {
+        IVec2::new(
+            (x / HEATMAP_CELL_SIZE).floor() as i32,
+            (y / HEATMAP_CELL_SIZE).floor() as i32,
+        )
+    };
+
+    let mut visits: HashMap<IVec2, u32> = HashMap::new();
+    let mut crashes: HashMap<IVec2, u32> = HashMap::new();
+    for attempt in attempts {
+        let mut cells: Vec<IVec2> = attempt.trajectory.iter().copied().map(to_cell).collect();
+        cells.sort_by_key(|cell| (cell.x, cell.y));
+        cells.dedup();
+        for cell in cells {
+            *visits.entry(cell).or_default() += 1;
+        }
+
+        if attempt.crashed {
+            if let Some(&end) = attempt.trajectory.last() {
+                *crashes.entry(to_cell(end)).or_default() += 1;
+            }
+        }
+    }
+    (visits, crashes)
+}
+
+fn spawn_cells(commands: &mut Commands, counts: &HashMap<IVec2, u32>, color: Srgba, z: f32) {
+    let max_count = counts.values().copied().max().unwrap_or(1) as f32;
+    let size = HEATMAP_CELL_SIZE * WORLD_TO_SCREEN_SCALE;
+    for (cell, count) in counts {
+        let density = *count as f32 / max_count;
+        commands.spawn((
+            Sprite {
+                color: color.with_alpha(0.1 + 0.5 * density).into(),
+                custom_size: Some(Vec2::splat(size)),
+                ..default()
+            },
+            Transform::from_xyz(0.0, 0.0, z),
+            HeatmapCell {
+                world_pos: (cell.as_vec2() + Vec2::splat(0.5)) * HEATMAP_CELL_SIZE,
+            },
+        ));
+    }
+}
+
+// Rebuilds the heatmap when it's toggled, the level changes or new attempts come in,
+// and keeps the cells in place as the camera moves
+#[allow(clippy::too_many_arguments)]
+pub fn update_heatmap(
+    mut commands: Commands,
+    view_options: Res<ViewOptions>,
+    mut dirty: ResMut<HeatmapDirty>,
+    camera_state: Res<CameraState>,
+    history: Res<Persistent<AttemptHistory>>,
+    level: Res<CurrentLevel>,
+    level_manager: Res<LevelManager>,
+    mut shown_for: Local<Option<(bool, usize)>>,
+    mut cells: Query<(Entity, &HeatmapCell, &mut Transform)>,
+) {
+    let Some(&(level_num, _)) = level_manager
+        .available_levels
+        .iter()
+        .find(|(_, name)| name == &level.config.name)
+    else {
+        return;
+    };
+
+    let wanted = (view_options.show_heatmap, level_num);
+    if dirty.0 || *shown_for != Some(wanted) {
+        for (entity, _, _) in cells.iter() {
+            commands.entity(entity).despawn();
+        }
+        if view_options.show_heatmap {
+            let (visits, crashes) = accumulate(persistence::get_attempts(level_num, &history));
+            spawn_cells(&mut commands, &visits, Srgba::new(1.0, 0.8, 0.0, 1.0), 0.3);
+            spawn_cells(
+                &mut commands,
+                &crashes,
+                Srgba::new(1.0, 0.0, 0.0, 1.0),
+                0.35,
+            );
+        }
+        *shown_for = Some(wanted);
+        dirty.0 = false;
+        return;
+    }
+
+    for (_, cell, mut transform) in cells.iter_mut() {
+        let screen_pos = world_to_screen(cell.world_pos, &camera_state);
+        transform.translation.x = screen_pos.x;
+        transform.translation.y = screen_pos.y;
+        transform.scale = Vec3::new(camera_state.zoom(), camera_state.zoom(), 1.0);
+    }
+}
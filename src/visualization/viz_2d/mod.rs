@@ -1,3 +1,8 @@
+pub mod camera;
 pub mod components;
+pub mod ghost;
+pub mod heatmap;
 pub mod particles;
+pub mod staging;
 pub mod systems;
+pub mod trail;
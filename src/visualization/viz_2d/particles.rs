@@ -2,9 +2,14 @@ use bevy::prelude::*;
 use rand::Rng;
 
 use super::components::LevelSpecific;
+use crate::audio::{self, AudioSettings};
+use crate::levels::CurrentLevel;
 use crate::simulation::LanderState;
 use crate::visualization::common::{world_to_screen, CameraState};
 
+// Particle jitter comes from the thread RNG, not the run seed: it's only for looks and
+// never feeds back into the simulation, so runs stay repeatable without it
+
 // Constants for particle system
 const PARTICLE_LIFETIME: f32 = 0.5;
 const PARTICLE_SIZE: f32 = 2.0;
@@ -15,6 +20,22 @@ const PARTICLE_BOUNCE_DAMPING: f32 = 0.1;
 const PARTICLE_GROUND_Y: f32 = 0.1;
 const LANDER_HEIGHT: f32 = 3.0; // Duplicated from constants.rs for particle positioning
 
+const RCS_PUFF_LIFETIME: f32 = 0.2;
+const RCS_PUFF_SIZE: f32 = 1.5;
+const RCS_PUFF_SPEED: f32 = 80.0;
+const RCS_PUFF_COUNT_PER_SPAWN: i32 = 3;
+
+// Dust blown off the surface by the exhaust near touchdown
+const DUST_ALTITUDE: f32 = 2.5; // Height of the nozzle above the ground where dust starts (m)
+const DUST_LIFETIME: f32 = 0.8;
+const DUST_SIZE: f32 = 2.5;
+const DUST_SPEED: f32 = 120.0;
+const DUST_COUNT_PER_SPAWN: f32 = 8.0;
+
+// Most exhaust, puff and dust particles alive at once. Spawning pauses at the cap so
+// long burns don't pile up entities; the one-off explosion isn't limited.
+const MAX_LIVE_PARTICLES: usize = 800;
+
 const EXPLOSION_PARTICLE_COUNT_MIN: usize = 100;
 const EXPLOSION_PARTICLE_COUNT_MAX: usize = 200;
 const EXPLOSION_PARTICLE_SPEED: f32 = 200.0;
@@ -41,7 +62,8 @@ fn spawn_particle(
     lander_pos: Vec2,
     base_position: Vec2,
     particle_direction: Vec2,
-    camera_offset: Vec2,
+    color: Color,
+    camera: &CameraState,
 ) {
     let mut rng = rand::thread_rng();
     let spread = PARTICLE_SPREAD;
@@ -54,11 +76,11 @@ fn spawn_particle(
 
     let offset = Vec2::new(rng.gen_range(-0.2..0.2), rng.gen_range(0.0..0.5));
     let world_pos = lander_pos + base_position + offset;
-    let screen_pos = world_to_screen(world_pos, camera_offset);
+    let screen_pos = world_to_screen(world_pos, camera);
 
     commands.spawn((
         Sprite {
-            color: Color::srgba(0.8, 0.3, 0.2, 0.8),
+            color,
             custom_size: Some(Vec2::new(PARTICLE_SIZE, PARTICLE_SIZE)),
             ..default()
         },
@@ -72,6 +94,65 @@ fn spawn_particle(
     ));
 }
 
+// Short-lived puff from an attitude thruster at `base_position` from the lander center
+fn spawn_rcs_puff(
+    commands: &mut Commands,
+    lander_pos: Vec2,
+    base_position: Vec2,
+    puff_direction: Vec2,
+    camera: &CameraState,
+) {
+    let mut rng = rand::thread_rng();
+    let angle_offset = rng.gen_range(-PARTICLE_SPREAD..PARTICLE_SPREAD);
+    let direction = Vec2::from_angle(angle_offset).rotate(puff_direction);
+    let speed = RCS_PUFF_SPEED * rng.gen_range(0.8..1.2);
+
+    let world_pos = lander_pos + base_position;
+    let screen_pos = world_to_screen(world_pos, camera);
+
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(0.9, 0.9, 1.0, 0.8),
+            custom_size: Some(Vec2::new(RCS_PUFF_SIZE, RCS_PUFF_SIZE)),
+            ..default()
+        },
+        Transform::from_xyz(screen_pos.x, screen_pos.y, 0.5),
+        ExhaustParticle {
+            lifetime: Timer::from_seconds(RCS_PUFF_LIFETIME, TimerMode::Once),
+            velocity: direction * speed,
+            world_pos,
+        },
+        LevelSpecific,
+    ));
+}
+
+// Grey dust thrown sideways along the surface from where the exhaust hits it
+fn spawn_dust(commands: &mut Commands, impingement: Vec2, camera: &CameraState) {
+    let mut rng = rand::thread_rng();
+    let side = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+    let direction = Vec2::new(side, rng.gen_range(0.05..0.4)).normalize();
+    let speed = DUST_SPEED * rng.gen_range(0.5..1.2);
+
+    let world_pos = impingement + Vec2::new(rng.gen_range(-0.3..0.3), PARTICLE_GROUND_Y);
+    let screen_pos = world_to_screen(world_pos, camera);
+    let shade = rng.gen_range(0.5..0.7);
+
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(shade, shade * 0.97, shade * 0.92, 0.7),
+            custom_size: Some(Vec2::new(DUST_SIZE, DUST_SIZE)),
+            ..default()
+        },
+        Transform::from_xyz(screen_pos.x, screen_pos.y, 0.5),
+        ExhaustParticle {
+            lifetime: Timer::from_seconds(DUST_LIFETIME * rng.gen_range(0.7..1.0), TimerMode::Once),
+            velocity: direction * speed,
+            world_pos,
+        },
+        LevelSpecific,
+    ));
+}
+
 // Make something Rico would appreciate
 pub fn kaboom(
     commands: &mut Commands,
@@ -110,6 +191,14 @@ pub fn kaboom(
     }
 }
 
+// Take up to `wanted` new particles from what's left under the cap
+fn take_budget(budget: &mut usize, wanted: i32) -> usize {
+    let granted = (wanted.max(0) as usize).min(*budget);
+    *budget -= granted;
+    granted
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn particle_system(
     mut commands: Commands,
     time: Res<Time>,
@@ -123,11 +212,15 @@ pub fn particle_system(
         Query<(Entity, &mut Transform, &mut ExhaustParticle)>,
     )>,
     lander_state: Res<LanderState>,
+    level: Res<CurrentLevel>,
+    asset_server: Res<AssetServer>,
+    audio_settings: Res<AudioSettings>,
 ) {
     let dt = time.delta_secs();
 
     // Update existing particles
     let mut to_despawn = Vec::new();
+    let live_particles;
     {
         let mut particle_query = query_set.p1();
         for (entity, mut transform, mut particle) in particle_query.iter_mut() {
@@ -136,10 +229,13 @@ pub fn particle_system(
             if particle.lifetime.finished() {
                 to_despawn.push(entity);
             } else {
+                // Velocities are in pixels at the default zoom; the particles live in the
+                // world so they stay put under zooming and panning
                 let delta = particle.velocity * dt;
-                transform.translation.x += delta.x;
-                transform.translation.y += delta.y;
                 particle.world_pos += delta / crate::visualization::WORLD_TO_SCREEN_SCALE;
+                let screen_pos = world_to_screen(particle.world_pos, &camera_state);
+                transform.translation.x = screen_pos.x;
+                transform.translation.y = screen_pos.y;
 
                 if particle.world_pos.y <= PARTICLE_GROUND_Y {
                     particle.world_pos.y = PARTICLE_GROUND_Y;
@@ -152,7 +248,9 @@ pub fn particle_system(
                 }
             }
         }
+        live_particles = particle_query.iter().len() - to_despawn.len();
     }
+    let mut budget = MAX_LIVE_PARTICLES.saturating_sub(live_particles);
 
     for entity in to_despawn {
         commands.entity(entity).despawn();
@@ -169,34 +267,90 @@ pub fn particle_system(
                 lander_state.velocity,
                 lander_transform,
             );
+            audio::play_explosion(&mut commands, &asset_server, &audio_settings);
             camera_state.explosion_spawned = true;
         }
     }
 
     // Spawn new particles
-    if lander_state.thrust_level > 0.0 && !lander_state.landed && !lander_state.crashed {
+    let main_engine_on = lander_state.thrust_level > 0.0;
+    let rcs_on = lander_state.rcs_level != 0.0;
+    if (main_engine_on || rcs_on) && !lander_state.landed && !lander_state.crashed {
         timer.0.tick(time.delta());
-        if timer.0.just_finished() {
-            let num_particles =
-                (lander_state.thrust_level * PARTICLE_COUNT_PER_SPAWN as f32) as i32;
-            let exhaust_angle =
-                lander_state.rotation + lander_state.gimbal_angle + std::f32::consts::FRAC_PI_2;
-            let exhaust_direction = -Vec2::new(exhaust_angle.cos(), exhaust_angle.sin());
-
-            let base_offset = Vec2::new(
-                lander_state.rotation.sin() * LANDER_HEIGHT / 2.0,
-                -lander_state.rotation.cos() * LANDER_HEIGHT / 2.0,
-            );
+    }
+    if !timer.0.just_finished() || lander_state.landed || lander_state.crashed {
+        return;
+    }
 
-            for _ in 0..num_particles {
-                spawn_particle(
+    // Attitude thrusters fire as a pair at the top and bottom of the lander, pushing
+    // them in opposite directions to turn it
+    if rcs_on {
+        let right = Vec2::new(lander_state.rotation.cos(), lander_state.rotation.sin());
+        let up = Vec2::new(-right.y, right.x);
+        let num_puffs =
+            ((lander_state.rcs_level.abs() * RCS_PUFF_COUNT_PER_SPAWN as f32).ceil()) as i32;
+        let sign = lander_state.rcs_level.signum();
+        for (position, direction) in [
+            (up * LANDER_HEIGHT / 2.0, right * sign),
+            (-up * LANDER_HEIGHT / 2.0, -right * sign),
+        ] {
+            for _ in 0..take_budget(&mut budget, num_puffs) {
+                spawn_rcs_puff(
                     &mut commands,
                     lander_state.position,
-                    base_offset,
-                    exhaust_direction,
-                    camera_state.target_offset,
+                    position,
+                    direction,
+                    &camera_state,
                 );
             }
         }
     }
+
+    if main_engine_on {
+        let visuals = &level.config.visuals;
+        let num_particles = (lander_state.thrust_level
+            * visuals.particle_intensity
+            * PARTICLE_COUNT_PER_SPAWN as f32) as i32;
+        let [red, green, blue, alpha] = visuals.exhaust_color;
+        let color = Color::srgba(red, green, blue, alpha);
+        let exhaust_angle =
+            lander_state.rotation + lander_state.gimbal_angle + std::f32::consts::FRAC_PI_2;
+        let exhaust_direction = -Vec2::new(exhaust_angle.cos(), exhaust_angle.sin());
+
+        let base_offset = Vec2::new(
+            lander_state.rotation.sin() * LANDER_HEIGHT / 2.0,
+            -lander_state.rotation.cos() * LANDER_HEIGHT / 2.0,
+        );
+
+        for _ in 0..take_budget(&mut budget, num_particles) {
+            spawn_particle(
+                &mut commands,
+                lander_state.position,
+                base_offset,
+                exhaust_direction,
+                color,
+                &camera_state,
+            );
+        }
+
+        // Close to flat ground the plume hits the surface and kicks up dust, more of it
+        // the lower and harder the engine is firing
+        let config = &level.config;
+        let nozzle = lander_state.position + base_offset;
+        let nozzle_height = config.height_above_ground(nozzle);
+        if config.physics.planet_radius.is_none()
+            && exhaust_direction.y < -0.1
+            && (0.0..DUST_ALTITUDE).contains(&nozzle_height)
+        {
+            let reach = nozzle_height / -exhaust_direction.y;
+            let hit_x = (nozzle + exhaust_direction * reach).x;
+            let impingement = Vec2::new(hit_x, config.ground_height(hit_x));
+            let closeness = 1.0 - nozzle_height / DUST_ALTITUDE;
+            let num_dust =
+                (lander_state.thrust_level * closeness * DUST_COUNT_PER_SPAWN).ceil() as i32;
+            for _ in 0..take_budget(&mut budget, num_dust) {
+                spawn_dust(&mut commands, impingement, &camera_state);
+            }
+        }
+    }
 }
@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+
+use super::components::LevelSpecific;
+use crate::constants::{LANDER_HEIGHT, LANDER_WIDTH};
+use crate::levels::CurrentLevel;
+use crate::simulation::LanderState;
+use crate::visualization::common::*;
+
+const STAGE_HEIGHT: f32 = 0.6; // meters
+
+// Speed the stage is pushed away from the lander at separation (m/s)
+const SEPARATION_SPEED: f32 = 1.0;
+
+// Dropped stage falling away from the lander. It's only drawn, the simulation
+// doesn't collide with it.
+#[derive(Component)]
+pub struct JettisonedStage {
+    pub position: Vec2, // World position (meters)
+    pub velocity: Vec2,
+    pub rotation: f32,
+    pub elapsed: f32, // Simulation time the stage was last advanced to
+}
+
+// Spawn the stage when the lander jettisons it, fly it ballistically in step with the
+// simulation clock (so it freezes while paused) and remove it again on reset
+pub fn update_jettisoned_stage(
+    mut commands: Commands,
+    mut stage_query: Query<(Entity, &mut JettisonedStage, &mut Transform)>,
+    lander_state: Res<LanderState>,
+    level: Res<CurrentLevel>,
+    camera_state: Res<CameraState>,
+) {
+    if !lander_state.jettisoned {
+        for (entity, _, _) in stage_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    if stage_query.is_empty() {
+        // Separate from the base of the lander, pushed out along its body axis
+        let down = Vec2::new(lander_state.rotation.sin(), -lander_state.rotation.cos());
+        let position = lander_state.position + down * (LANDER_HEIGHT + STAGE_HEIGHT) / 2.0;
+        let screen_pos = world_to_screen(position, &camera_state);
+        commands.spawn((
+            Sprite {
+                color: Color::srgb(0.5, 0.5, 0.55),
+                custom_size: Some(Vec2::new(LANDER_WIDTH, STAGE_HEIGHT) * WORLD_TO_SCREEN_SCALE),
+                ..default()
+            },
+            Transform::from_translation(screen_pos.extend(0.9))
+                .with_rotation(Quat::from_rotation_z(lander_state.rotation)),
+            JettisonedStage {
+                position,
+                velocity: lander_state.velocity + down * SEPARATION_SPEED,
+                rotation: lander_state.rotation,
+                elapsed: lander_state.elapsed,
+            },
+            LevelSpecific,
+        ));
+        return;
+    }
+
+    let physics = &level.config.physics;
+    for (_, mut stage, mut transform) in stage_query.iter_mut() {
+        let dt = (lander_state.elapsed - stage.elapsed).max(0.0);
+        stage.elapsed = lander_state.elapsed;
+
+        if level.config.height_above_ground(stage.position) > STAGE_HEIGHT / 2.0 {
+            let gravity = physics.gravity_at(stage.position);
+            stage.velocity += gravity * dt;
+            let velocity = stage.velocity;
+            stage.position += velocity * dt;
+        } else {
+            // Landed: rest on the surface
+            stage.velocity = Vec2::ZERO;
+        }
+
+        let screen_pos = world_to_screen(stage.position, &camera_state);
+        transform.translation.x = screen_pos.x;
+        transform.translation.y = screen_pos.y;
+        transform.rotation = Quat::from_rotation_z(stage.rotation);
+        transform.scale = Vec3::new(camera_state.zoom(), camera_state.zoom(), 1.0);
+    }
+}
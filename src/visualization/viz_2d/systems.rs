@@ -6,12 +6,15 @@ use bevy::color::palettes::css::*;
 use bevy::prelude::*;
 
 use super::components::*;
+use super::trail::TrajectoryTrail;
 use crate::constants::{LANDER_HEIGHT, LANDER_WIDTH};
-use crate::levels::{CurrentLevel, Reference};
+use crate::levels::{CurrentLevel, Objective, Reference, Terrain};
 use crate::simulation::LanderState;
 use crate::visualization::common::*;
 
-const GRID_SPACING: f32 = 10.0; // 10 meter spacing
+const GRID_SPACING: f32 = 10.0; // Default 10 meter spacing
+const GRID_EXTENT: f32 = 100.0; // Grid reaches this far from the lander in each direction (m)
+const MAX_GRID_LINES: f32 = 50.0; // Per direction, caps the line count for very fine spacings
 
 fn create_triangle_mesh() -> Mesh {
     let mut mesh = Mesh::new(
@@ -39,63 +42,125 @@ fn create_triangle_mesh() -> Mesh {
     mesh
 }
 
+// How far the terrain reaches past its first and last point, and below its lowest one (m)
+const TERRAIN_EXTENT: f32 = 1000.0;
+const TERRAIN_DEPTH: f32 = 20.0;
+
+// Filled polygon under the terrain profile, in screen units relative to the world origin
+fn create_terrain_mesh(terrain: &Terrain) -> Mesh {
+    let mut mesh = Mesh::new(
+        bevy::render::render_resource::PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+
+    let mut profile = terrain.points.clone();
+    if let (Some(&(first_x, first_y)), Some(&(last_x, last_y))) =
+        (terrain.points.first(), terrain.points.last())
+    {
+        profile.insert(0, (first_x - TERRAIN_EXTENT, first_y));
+        profile.push((last_x + TERRAIN_EXTENT, last_y));
+    }
+    let bottom = profile.iter().map(|(_, y)| *y).fold(0.0_f32, f32::min) - TERRAIN_DEPTH;
+
+    // A top and a bottom vertex per profile point, two triangles per segment
+    let mut vertices = Vec::new();
+    for (x, y) in &profile {
+        vertices.push([x * WORLD_TO_SCREEN_SCALE, y * WORLD_TO_SCREEN_SCALE, 0.0]);
+        vertices.push([
+            x * WORLD_TO_SCREEN_SCALE,
+            bottom * WORLD_TO_SCREEN_SCALE,
+            0.0,
+        ]);
+    }
+    let mut indices = Vec::new();
+    for i in 0..profile.len().saturating_sub(1) as u32 {
+        let (top, low) = (i * 2, i * 2 + 1);
+        indices.extend([top, low, top + 2, top + 2, low, low + 2]);
+    }
+    let normals = vec![[0.0, 0.0, 1.0]; vertices.len()];
+    let uvs = vec![[0.0, 0.0]; vertices.len()];
+
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_indices(bevy::render::mesh::Indices::U32(indices));
+    mesh
+}
+
 #[derive(Resource, Default)]
 pub struct ResetVisualization(pub bool);
 
+#[allow(clippy::too_many_arguments)]
 pub fn reset_visualization_system(
     mut commands: Commands,
     mut reset_flag: ResMut<ResetVisualization>,
     meshes: ResMut<Assets<Mesh>>,
     materials: ResMut<Assets<ColorMaterial>>,
     level: Res<CurrentLevel>,
+    camera_state: ResMut<CameraState>,
     query: Query<Entity, With<LevelSpecific>>,
+    mut trail: ResMut<TrajectoryTrail>,
 ) {
     if reset_flag.0 {
         // Cleanup
         for entity in query.iter() {
             commands.entity(entity).despawn();
         }
+        trail.clear();
 
         // Respawn
-        spawn_visualization(commands, meshes, materials, level);
+        spawn_visualization(commands, meshes, materials, level, camera_state);
 
         reset_flag.0 = false;
     }
 }
 
-#[derive(Component)]
-pub struct LevelSpecific;
-
 pub fn spawn_visualization(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     level: Res<CurrentLevel>,
+    mut camera_state: ResMut<CameraState>,
 ) {
-    commands.insert_resource(CameraState::default());
+    // Start following again, but keep the user's zoom
+    *camera_state = CameraState {
+        scale: camera_state.scale,
+        ..default()
+    };
     let center_offset = -(RIGHT_PANEL_WIDTH / 2.0);
     let config = &level.config;
 
-    // Spawn ground
+    // Spawn ground: the terrain profile if the level has one, flat otherwise
     let ground_width = 10000.0;
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.3, 0.3, 0.3),
-            custom_size: Some(Vec2::new(ground_width, 200.0)),
-            ..default()
-        },
-        Transform::from_xyz(
-            center_offset + ground_width / 4.0,
-            GROUND_OFFSET - 100.0,
-            0.25,
-        ),
-        Ground,
-        LevelSpecific,
-    ));
+    if let Some(terrain) = config.flat_terrain() {
+        let origin = world_to_screen(Vec2::ZERO, &camera_state);
+        commands.spawn((
+            Mesh2d(meshes.add(create_terrain_mesh(terrain))),
+            MeshMaterial2d(materials.add(ColorMaterial::from_color(Color::srgb(0.3, 0.3, 0.3)))),
+            Transform::from_xyz(origin.x, origin.y, 0.25),
+            TerrainMesh,
+            LevelSpecific,
+        ));
+    } else {
+        commands.spawn((
+            Sprite {
+                color: Color::srgb(0.3, 0.3, 0.3),
+                custom_size: Some(Vec2::new(ground_width, 200.0)),
+                ..default()
+            },
+            Transform::from_xyz(
+                center_offset + ground_width / 4.0,
+                GROUND_OFFSET - 100.0,
+                0.25,
+            ),
+            Ground,
+            LevelSpecific,
+        ));
+    }
 
     // Spawn success zone
     let initial_pos = Vec2::new(config.initial.x0, config.initial.y0);
-    let screen_pos = world_to_screen(initial_pos, Vec2::ZERO);
+    let screen_pos = world_to_screen(initial_pos, &camera_state);
 
     // Get dimensions from level config
     let zone_width = (config.success.position_box.x_max - config.success.position_box.x_min)
@@ -129,6 +194,40 @@ pub fn spawn_visualization(
         ));
     }
 
+    // Ascent target altitude line
+    if let Objective::Ascent {
+        target_altitude, ..
+    } = config.success.objective
+    {
+        commands.spawn((
+            Sprite {
+                color: Color::srgba(0.0, 0.8, 0.0, 0.6),
+                custom_size: Some(Vec2::new(ground_width, 2.0)),
+                ..default()
+            },
+            Transform::from_xyz(center_offset, 0.0, 0.5),
+            TargetAltitude(target_altitude),
+            LevelSpecific,
+        ));
+    }
+
+    // No-fly obstacles
+    for obstacle in &config.failure.obstacles {
+        let (min, max) = obstacle.world_bounds(&config.initial);
+        let center = (min + max) / 2.0;
+        let screen_pos = world_to_screen(center, &camera_state);
+        commands.spawn((
+            Sprite {
+                color: Color::srgba(0.8, 0.1, 0.1, 0.5),
+                custom_size: Some(((max - min) * WORLD_TO_SCREEN_SCALE).max(Vec2::ONE)),
+                ..default()
+            },
+            Transform::from_xyz(screen_pos.x, screen_pos.y, 0.45),
+            Obstacle(center),
+            LevelSpecific,
+        ));
+    }
+
     // // Spawn failure bounds if they exist
     // if let Some(bounds) = &config.failure.bounds {
     //     let bounds_width = (bounds.x_max - bounds.x_min) * WORLD_TO_SCREEN_SCALE;
@@ -165,6 +264,7 @@ pub fn update_grid_lines(
     grid_query: Query<Entity, With<GridSystem>>,
     camera_state: Res<CameraState>,
     lander_state: Res<LanderState>,
+    level: Res<CurrentLevel>,
 ) {
     // Get the grid parent entity, or create one if it doesn't exist
     let grid_entity = if let Some(entity) = grid_query.iter().next() {
@@ -183,35 +283,50 @@ pub fn update_grid_lines(
             .id()
     };
 
-    // Work in world coordinates first
-    let view_center = lander_state.position;
-    let num_lines = 10; // Number of grid spacings to extend in each direction from center
+    if !level.config.show_grid {
+        return;
+    }
+
+    // Work in world coordinates first, around the spacecraft or wherever the user panned to
+    let view_center = if camera_state.following {
+        lander_state.position
+    } else {
+        screen_to_world(Vec2::new(-(RIGHT_PANEL_WIDTH / 2.0), 0.0), &camera_state)
+    };
+
+    // The grid reaches further when zoomed out, with coarser lines to keep the count down
+    let extent = GRID_EXTENT / camera_state.zoom().min(1.0);
+    let mut spacing = level
+        .config
+        .grid_spacing
+        .unwrap_or(GRID_SPACING)
+        .max(GRID_EXTENT / MAX_GRID_LINES);
+    while extent / spacing > MAX_GRID_LINES {
+        spacing *= 10.0;
+    }
 
-    // Calculate world-space bounds centered on spacecraft
-    let line_length = GRID_SPACING * num_lines as f32;
+    // Calculate world-space bounds centered on the view
+    let line_length = extent.max(spacing);
 
     // Calculate grid line positions in world space
-    let start_x = ((view_center.x - line_length) / GRID_SPACING).floor() * GRID_SPACING;
-    let end_x = ((view_center.x + line_length) / GRID_SPACING).ceil() * GRID_SPACING;
-    let start_y = ((view_center.y - line_length) / GRID_SPACING).floor() * GRID_SPACING;
-    let end_y = ((view_center.y + line_length) / GRID_SPACING).ceil() * GRID_SPACING;
+    let start_x = ((view_center.x - line_length) / spacing).floor() * spacing;
+    let end_x = ((view_center.x + line_length) / spacing).ceil() * spacing;
+    let start_y = ((view_center.y - line_length) / spacing).floor() * spacing;
+    let end_y = ((view_center.y + line_length) / spacing).ceil() * spacing;
 
     // Calculate world height for vertical lines (based on lander position)
     let vertical_world_height = line_length * 2.0; // Same scale as width
-    let vertical_screen_height = vertical_world_height * WORLD_TO_SCREEN_SCALE;
+    let vertical_screen_height = vertical_world_height * camera_state.scale;
 
     // Calculate world width for horizontal lines (based on lander position)
     let horizontal_world_width = line_length * 2.0;
-    let horizontal_screen_width = horizontal_world_width * WORLD_TO_SCREEN_SCALE;
+    let horizontal_screen_width = horizontal_world_width * camera_state.scale;
 
     // Spawn vertical lines
     let mut x = start_x;
     while x <= end_x {
         // Convert world X to screen X
-        let screen_pos = world_to_screen(
-            Vec2::new(x, lander_state.position.y),
-            camera_state.target_offset,
-        );
+        let screen_pos = world_to_screen(Vec2::new(x, view_center.y), &camera_state);
 
         commands
             .spawn((
@@ -226,16 +341,13 @@ pub fn update_grid_lines(
                 GridSystem,
             ))
             .set_parent(grid_entity);
-        x += GRID_SPACING;
+        x += spacing;
     }
 
     // Spawn horizontal lines
     let mut y = start_y;
     while y <= end_y {
-        let screen_pos = world_to_screen(
-            Vec2::new(lander_state.position.x, y),
-            camera_state.target_offset,
-        );
+        let screen_pos = world_to_screen(Vec2::new(view_center.x, y), &camera_state);
         commands
             .spawn((
                 Sprite {
@@ -249,24 +361,30 @@ pub fn update_grid_lines(
                 GridSystem,
             ))
             .set_parent(grid_entity);
-        y += GRID_SPACING;
+        y += spacing;
     }
 }
 
-fn calculate_view_offset(lander_pos: Vec2) -> Vec2 {
-    // Always calculate full offset needed to center the lander
-    let screen_pos_without_offset = Vec2::new(
-        lander_pos.x * WORLD_TO_SCREEN_SCALE,
-        lander_pos.y * WORLD_TO_SCREEN_SCALE + GROUND_OFFSET,
-    );
+// Largest camera look-ahead in meters, so fast descents don't throw the lander off screen
+const MAX_CAMERA_LEAD: f32 = 30.0;
+// Time constant for easing the look-ahead towards its target (seconds)
+const CAMERA_LEAD_SMOOTHING: f32 = 0.5;
+
+fn calculate_view_offset(lander_pos: Vec2, lead: Vec2, scale: f32) -> Vec2 {
+    // Follow a point ahead of the lander in its direction of motion
+    let follow_pos = lander_pos + lead;
+
+    // Always calculate full offset needed to center the follow point
+    let screen_pos_without_offset =
+        Vec2::new(follow_pos.x * scale, follow_pos.y * scale + GROUND_OFFSET);
 
     // For X: always follow to keep centered horizontally
     let x_offset = screen_pos_without_offset.x;
 
-    // For Y: smoothly transition based on height
-    let ground_view_height = MIN_VIEW_HEIGHT * WORLD_TO_SCREEN_SCALE;
+    // For Y: smoothly transition based on the lander's height
+    let ground_view_height = MIN_VIEW_HEIGHT * scale;
     let full_follow_height = ground_view_height * 2.0;
-    let screen_y = lander_pos.y * WORLD_TO_SCREEN_SCALE;
+    let screen_y = lander_pos.y * scale;
 
     let y_offset = if screen_y > full_follow_height {
         // Above transition: full vertical follow
@@ -283,15 +401,7 @@ fn calculate_view_offset(lander_pos: Vec2) -> Vec2 {
     Vec2::new(x_offset, y_offset)
 }
 
-pub(crate) fn world_to_screen(pos: Vec2, camera_offset: Vec2) -> Vec2 {
-    let center_offset = -(RIGHT_PANEL_WIDTH / 2.0);
-
-    Vec2::new(
-        pos.x * WORLD_TO_SCREEN_SCALE + center_offset - camera_offset.x,
-        pos.y * WORLD_TO_SCREEN_SCALE + GROUND_OFFSET - camera_offset.y,
-    )
-}
-
+#[allow(clippy::type_complexity)]
 pub fn update_visualization(
     mut query_set: ParamSet<(
         Query<&mut Transform, With<Lander>>,
@@ -300,59 +410,114 @@ pub fn update_visualization(
             &Sprite,
             Option<&TargetZone>,
             Option<&Ground>,
+            Option<&TargetAltitude>,
+            Option<&Obstacle>,
         )>,
+        Query<&mut Transform, With<TerrainMesh>>,
     )>,
     mut camera_state: ResMut<CameraState>,
     lander_state: Res<LanderState>,
     level: Res<CurrentLevel>,
+    view_options: Res<ViewOptions>,
+    time: Res<Time>,
 ) {
-    // Calculate view offset based on lander position
-    let offset = calculate_view_offset(lander_state.position);
-    camera_state.target_offset = offset;
+    // Ease the look-ahead towards the velocity lead so touchdowns don't jerk the camera
+    let target_lead =
+        (lander_state.velocity * view_options.camera_lead).clamp_length_max(MAX_CAMERA_LEAD);
+    let blend = 1.0 - (-time.delta_secs() / CAMERA_LEAD_SMOOTHING).exp();
+    camera_state.lead = camera_state.lead.lerp(target_lead, blend);
+
+    // Calculate view offset based on lander position, unless the user has panned away
+    if camera_state.following {
+        let offset =
+            calculate_view_offset(lander_state.position, camera_state.lead, camera_state.scale);
+        camera_state.target_offset = offset;
+    }
+    // Sprites and meshes are built at the default zoom and scaled to the current one
+    let zoom = camera_state.zoom();
 
     // Update lander position
     if let Ok(mut transform) = query_set.p0().get_single_mut() {
-        let screen_pos = world_to_screen(lander_state.position, offset);
+        // Squash the lander by the leg compression, keeping its base on the ground
+        let compression = lander_state.leg_compression.min(LANDER_HEIGHT / 2.0);
+        let up = Vec2::new(-lander_state.rotation.sin(), lander_state.rotation.cos());
+        let center = lander_state.position + up * compression / 2.0;
+        let screen_pos = world_to_screen(center, &camera_state);
         transform.translation.x = screen_pos.x;
         transform.translation.y = screen_pos.y;
         transform.rotation = Quat::from_rotation_z(lander_state.rotation);
+        transform.scale = Vec3::new(zoom, zoom * (1.0 - compression / LANDER_HEIGHT), 1.0);
+    }
+
+    // The terrain mesh is laid out in world units, so it only moves with the camera
+    if let Ok(mut transform) = query_set.p2().get_single_mut() {
+        let origin = world_to_screen(Vec2::ZERO, &camera_state);
+        transform.translation.x = origin.x;
+        transform.translation.y = origin.y;
+        transform.scale = Vec3::new(zoom, zoom, 1.0);
     }
 
     // Update ground and zone positions
     let mut ground_query = query_set.p1();
-    for (mut transform, sprite, target_zone, ground) in ground_query.iter_mut() {
-        if ground.is_some() {
+    for (mut transform, sprite, target_zone, ground, target_altitude, obstacle) in
+        ground_query.iter_mut()
+    {
+        if let Some(Obstacle(center)) = obstacle {
+            let screen_pos = world_to_screen(*center, &camera_state);
+            transform.translation.x = screen_pos.x;
+            transform.translation.y = screen_pos.y;
+            transform.scale = Vec3::new(zoom, zoom, 1.0);
+        } else if let Some(TargetAltitude(altitude)) = target_altitude {
+            // Keep the line under the lander horizontally, at the target altitude vertically
+            let screen_pos =
+                world_to_screen(Vec2::new(lander_state.position.x, *altitude), &camera_state);
+            transform.translation.x = screen_pos.x;
+            transform.translation.y = screen_pos.y;
+        } else if let (Some(_), Some(center), Some(radius)) = (
+            ground,
+            level.config.physics.planet_center(),
+            level.config.physics.planet_radius,
+        ) {
+            // Planet-center gravity: lay the ground tangent to the surface below the lander
+            let up = level.config.physics.local_up(lander_state.position);
+            let screen_pos = world_to_screen(center + up * radius, &camera_state);
+            let half_height = sprite.custom_size.map_or(0.0, |size| size.y / 2.0);
+            let screen_pos = screen_pos - up * half_height;
+            transform.translation.x = screen_pos.x;
+            transform.translation.y = screen_pos.y;
+            transform.rotation = Quat::from_rotation_z((-up.x).atan2(up.y));
+        } else if ground.is_some() {
             // Ground uses same position as landing zone
             let landing_center = Vec2::new(
                 (level.config.success.position_box.x_min + level.config.success.position_box.x_max)
                     / 2.0,
                 0.0,
             );
-            let screen_pos = world_to_screen(landing_center, offset);
+            let screen_pos = world_to_screen(landing_center, &camera_state);
             transform.translation.x = screen_pos.x;
             // Center the ground block using its height
             if let Some(size) = sprite.custom_size {
                 transform.translation.y = screen_pos.y - (size.y / 2.0);
             }
         } else if target_zone.is_some() {
+            transform.scale = Vec3::new(zoom, zoom, 1.0);
             // Only update position if this is an absolute reference zone
             match level.config.success.position_box.reference {
                 Reference::Absolute => {
                     // Landing zone centered on success zone
-                    let landing_zone_pos = Vec2::new(
-                        (level.config.success.position_box.x_min
-                            + level.config.success.position_box.x_max)
-                            / 2.0,
-                        0.0,
-                    );
-                    let screen_pos = world_to_screen(landing_zone_pos, offset);
+                    let landing_x = (level.config.success.position_box.x_min
+                        + level.config.success.position_box.x_max)
+                        / 2.0;
+                    let landing_zone_pos =
+                        Vec2::new(landing_x, level.config.ground_height(landing_x));
+                    let screen_pos = world_to_screen(landing_zone_pos, &camera_state);
                     transform.translation.x = screen_pos.x;
-                    transform.translation.y = screen_pos.y + 5.0; // Slight offset to stay above ground
+                    transform.translation.y = screen_pos.y + 5.0 * zoom; // Slight offset to stay above ground
                 }
                 Reference::Initial => {
                     // For hover target, track initial position
                     let initial_pos = Vec2::new(level.config.initial.x0, level.config.initial.y0);
-                    let screen_pos = world_to_screen(initial_pos, offset);
+                    let screen_pos = world_to_screen(initial_pos, &camera_state);
                     transform.translation.x = screen_pos.x;
                     transform.translation.y = screen_pos.y;
                 }
@@ -372,3 +537,133 @@ pub fn reset_lander_visibility(
         reset_flag.0 = false; // This is correct - we want to reset it after handling
     }
 }
+
+// Screen length of an arrow for the largest force the level can produce
+const FORCE_ARROW_LENGTH: f32 = 80.0;
+
+// Free-body diagram: gravity, thrust, wind, drag and net force drawn from the lander's center,
+// all with the same newtons-to-pixels scale
+pub fn draw_force_vectors(
+    mut gizmos: Gizmos,
+    view_options: Res<ViewOptions>,
+    camera_state: Res<CameraState>,
+    lander_state: Res<LanderState>,
+    level: Res<CurrentLevel>,
+) {
+    if !view_options.show_forces || lander_state.crashed {
+        return;
+    }
+
+    let config = &level.config;
+    let gravity = lander_state.gravity_force(config);
+    let thrust = lander_state.thrust_force(config) + lander_state.lateral_force(config);
+    let wind = lander_state.wind_force(config);
+    let drag = lander_state.drag_force(config);
+    let net = gravity + thrust + wind + drag;
+
+    // Scale against the larger of full thrust and the fully fueled weight so the
+    // arrows keep a fixed scale for the whole run
+    let max_weight =
+        (config.physics.gravity * (config.physics.dry_mass + config.initial.initial_fuel)).abs();
+    let reference_force = config.physics.max_thrust.max(max_weight);
+    if reference_force <= 0.0 {
+        return;
+    }
+    let scale = FORCE_ARROW_LENGTH / reference_force;
+
+    let origin = world_to_screen(lander_state.position, &camera_state);
+    for (force, color) in [
+        (gravity, ORANGE),
+        (thrust, DEEP_SKY_BLUE),
+        (wind, VIOLET),
+        (drag, SILVER),
+        (net, LIME),
+    ] {
+        if force.length() * scale >= 1.0 {
+            gizmos.arrow_2d(origin, origin + force * scale, color);
+        }
+    }
+}
+
+// Velocity arrow length per m/s and thrust arrow length at full throttle, before the gain (pixels)
+const VELOCITY_ARROW_SCALE: f32 = 4.0;
+const THRUST_ARROW_LENGTH: f32 = 60.0;
+
+// Velocity (green) and thrust (orange) arrows from the lander's center, both scaled
+// by the gain from the View menu
+pub fn draw_motion_vectors(
+    mut gizmos: Gizmos,
+    view_options: Res<ViewOptions>,
+    camera_state: Res<CameraState>,
+    lander_state: Res<LanderState>,
+    level: Res<CurrentLevel>,
+) {
+    if !view_options.show_motion_vectors || lander_state.crashed {
+        return;
+    }
+
+    let origin = world_to_screen(lander_state.position, &camera_state);
+    let gain = view_options.motion_vector_gain;
+
+    let velocity = lander_state.velocity * VELOCITY_ARROW_SCALE * gain;
+    if velocity.length() >= 1.0 {
+        gizmos.arrow_2d(origin, origin + velocity, GREEN);
+    }
+
+    // Thrust points along the engine, which is the body axis turned by the gimbal
+    let thrust = lander_state.thrust_force(&level.config).normalize_or_zero()
+        * lander_state.thrust_level
+        * THRUST_ARROW_LENGTH
+        * gain;
+    if thrust.length() >= 1.0 {
+        gizmos.arrow_2d(origin, origin + thrust, ORANGE);
+    }
+}
+
+// Length of the down-range and cross-range axis lines either side of the target (m)
+const RANGE_AXIS_LENGTH: f32 = 2000.0;
+
+// Down-range line from the start through the landing target, and the cross-range
+// line across it at the target
+pub fn draw_range_axes(
+    mut gizmos: Gizmos,
+    view_options: Res<ViewOptions>,
+    camera_state: Res<CameraState>,
+    level: Res<CurrentLevel>,
+) {
+    if !view_options.show_range_axes {
+        return;
+    }
+
+    let target = level.config.final_target();
+    let axis = level.config.downrange_axis();
+    for (direction, color) in [
+        (axis, Color::srgba(0.0, 1.0, 1.0, 0.5)),
+        (axis.perp(), Color::srgba(1.0, 0.0, 1.0, 0.5)),
+    ] {
+        let reach = direction * RANGE_AXIS_LENGTH;
+        gizmos.line_2d(
+            world_to_screen(target - reach, &camera_state),
+            world_to_screen(target + reach, &camera_state),
+            color,
+        );
+    }
+}
+
+// Waypoint rings: the next one highlighted, passed ones dimmed
+pub fn draw_waypoints(
+    mut gizmos: Gizmos,
+    camera_state: Res<CameraState>,
+    lander_state: Res<LanderState>,
+    level: Res<CurrentLevel>,
+) {
+    for (i, waypoint) in level.config.waypoints.iter().enumerate() {
+        let color = match i.cmp(&lander_state.waypoint_index) {
+            std::cmp::Ordering::Less => Color::srgba(0.5, 0.5, 0.5, 0.4),
+            std::cmp::Ordering::Equal => Color::from(YELLOW),
+            std::cmp::Ordering::Greater => Color::srgba(1.0, 1.0, 1.0, 0.6),
+        };
+        let center = world_to_screen(Vec2::new(waypoint.x, waypoint.y), &camera_state);
+        gizmos.circle_2d(center, waypoint.radius * camera_state.scale, color);
+    }
+}
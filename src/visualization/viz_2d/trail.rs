@@ -0,0 +1,68 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::simulation::LanderState;
+use crate::visualization::common::*;
+
+// Longest trail kept, older points drop off the tail
+const MAX_TRAIL_POINTS: usize = 500;
+// Skip points closer than this to the last one, so hovering doesn't use up the trail (m)
+const MIN_TRAIL_SPACING: f32 = 0.05;
+
+// Recent world positions of the lander, newest at the back
+#[derive(Resource, Default)]
+pub struct TrajectoryTrail {
+    pub points: VecDeque<Vec2>,
+    last_elapsed: f32, // Simulation time of the newest point
+}
+
+impl TrajectoryTrail {
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.last_elapsed = 0.0;
+    }
+}
+
+// Record the lander position each frame. A simulation time that went backwards
+// means the run was reset, so the trail starts over.
+pub fn record_trail(mut trail: ResMut<TrajectoryTrail>, lander_state: Res<LanderState>) {
+    if lander_state.elapsed < trail.last_elapsed {
+        trail.clear();
+    }
+    trail.last_elapsed = lander_state.elapsed;
+
+    let position = lander_state.position;
+    if trail
+        .points
+        .back()
+        .is_some_and(|last| last.distance(position) < MIN_TRAIL_SPACING)
+    {
+        return;
+    }
+    trail.points.push_back(position);
+    while trail.points.len() > MAX_TRAIL_POINTS {
+        trail.points.pop_front();
+    }
+}
+
+// Connected line through the trail, fading out towards the oldest point
+pub fn draw_trail(
+    mut gizmos: Gizmos,
+    view_options: Res<ViewOptions>,
+    camera_state: Res<CameraState>,
+    trail: Res<TrajectoryTrail>,
+) {
+    if !view_options.show_trail || trail.points.len() < 2 {
+        return;
+    }
+
+    let count = trail.points.len() as f32;
+    gizmos.linestrip_gradient_2d(trail.points.iter().enumerate().map(|(i, point)| {
+        let alpha = (i + 1) as f32 / count;
+        (
+            world_to_screen(*point, &camera_state),
+            Color::srgba(1.0, 1.0, 1.0, alpha * 0.8),
+        )
+    }));
+}